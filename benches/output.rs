@@ -0,0 +1,36 @@
+use arpx::Runtime;
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use std::env::current_dir;
+
+const OUTPUT_SIZE: u64 = 8 * 1024 * 1024;
+
+pub fn output_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("output");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(OUTPUT_SIZE));
+
+    for fixture in ["lines", "long_lines", "binary"] {
+        let mut fixture_path = current_dir().unwrap();
+        fixture_path.push("benches");
+        fixture_path.push("fixtures");
+        fixture_path.push("output");
+        fixture_path.push(fixture);
+        fixture_path.set_extension("yaml");
+
+        group.bench_function(fixture, |b| {
+            b.iter(|| {
+                Runtime::from_profile(
+                    black_box(&fixture_path.display().to_string()[..]),
+                    black_box(&["bench".to_string()]),
+                )
+                .unwrap()
+                .run()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, output_benchmark);
+criterion_main!(benches);
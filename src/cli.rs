@@ -5,11 +5,20 @@ pub struct Cli;
 impl Cli {
     #[must_use]
     pub fn run() -> ArgMatches {
-        return command!()
+        command!()
             .propagate_version(true)
             .arg_required_else_help(true)
             .arg(arg!(-f --file <FILE> "Path to profile"))
             .arg(arg!(-j --job <JOB> "Job in profile to run").multiple_occurrences(true))
+            .arg(
+                arg!(--"grace-period" <SECONDS> "Seconds to wait for processes to exit after an interrupt")
+                    .required(false),
+            )
+            .arg(
+                arg!(--"max-action-depth" <DEPTH> "Maximum length of a chain of actions before the runtime exits with an error")
+                    .required(false),
+            )
+            .arg(arg!(--"strip-ansi" "Strip ANSI escape sequences from process output when stdout is not a terminal"))
             .arg(arg!(-v --verbose))
             .arg(arg!(--debug))
             .subcommand(
@@ -23,6 +32,6 @@ impl Cli {
                             .required(false),
                     ),
             )
-            .get_matches();
+            .get_matches()
     }
 }
@@ -63,11 +63,11 @@
 //!     Process::new("database".to_string())
 //!         .command("run.sh".to_string())
 //!         .cwd("/path/to/project/database/".to_string())
-//!         .onsucceed(Some("db_recover".to_string())),
+//!         .onsucceed(vec!["db_recover".to_string()]),
 //!     Process::new("api".to_string())
 //!         .command("run.sh".to_string())
 //!         .cwd("/path/to/project/api/".to_string())
-//!         .onsucceed(Some("api_recover".to_string())),
+//!         .onsucceed(vec!["api_recover".to_string()]),
 //! ];
 //!
 //! let mut process_map = processes
@@ -81,8 +81,8 @@
 //!     Process::new("db_recover".to_string())
 //!         .command("self-heal.sh".to_string())
 //!         .cwd("/path/to/project/database/".to_string())
-//!         .onsucceed(Some("database".to_string()))
-//!         .onfail(Some("arpx_exit_error".to_string()))
+//!         .onsucceed(vec!["database".to_string()])
+//!         .onfail(vec!["arpx_exit_error".to_string()])
 //! );
 //!
 //! process_map.insert(
@@ -91,8 +91,8 @@
 //!         .command("self-heal.sh".to_string())
 //!         .cwd("/path/to/project/api/".to_string())
 //!         .log_monitors(vec!["db_permissions_error".to_string()])
-//!         .onsucceed(Some("api".to_string()))
-//!         .onfail(Some("arpx_exit_error".to_string())),
+//!         .onsucceed(vec!["api".to_string()])
+//!         .onfail(vec!["arpx_exit_error".to_string()]),
 //! );
 //!
 //! let mut log_monitor_map = HashMap::new();
@@ -102,7 +102,7 @@
 //!     LogMonitor::new("db_permissions_error".to_string())
 //!         .buffer_size(1)
 //!         .test("echo \"$ARPX_BUFFER\" | grep -q \"Access denied for user\"".to_string())
-//!         .ontrigger("arpx_exit_error".to_string())
+//!         .ontrigger(vec!["arpx_exit_error".to_string()])
 //! );
 //!
 //! let jobs = vec![Job::new(
@@ -217,9 +217,25 @@ pub use logs::Logs;
 pub use runtime::{
     ctx::Ctx,
     job::{
-        task::{log_monitor::LogMonitor, process::Process, Task},
+        task::{
+            completion::Completion,
+            log_monitor::{message::OutputStream, LogMonitor},
+            process::{
+                backoff::Backoff,
+                capture::{Capture, CaptureMode},
+                encoding::Encoding,
+                exit_condition::ExitCondition,
+                limits::Limits,
+                readiness::{Probe, Readiness},
+                restart::{Restart, RestartPolicy},
+                stdin::Stdin,
+                Process,
+            },
+            Task,
+        },
         Job,
     },
     local_bin::BinCommand,
+    shutdown::ExitError,
     Runtime,
 };
@@ -1,9 +1,13 @@
 mod cli;
 
 use anyhow::{Context, Result};
-use arpx::{BinCommand, Logs, Runtime};
+use arpx::{BinCommand, ExitError, Logs, Runtime};
 use cli::Cli;
 use log::{debug, LevelFilter};
+use std::{
+    io::{stdout, IsTerminal},
+    time::Duration,
+};
 
 fn main() -> Result<()> {
     let matches = Cli::run();
@@ -19,10 +23,7 @@ fn main() -> Result<()> {
 
     debug!("CLI returned matches: {:#?}", matches);
 
-    let path = match matches.value_of("file") {
-        Some(f) => f,
-        _ => "arpx.yaml",
-    };
+    let path = matches.value_of("file").unwrap_or("arpx.yaml");
     let jobs = match matches.values_of("job") {
         Some(jobs) => jobs.map(std::string::ToString::to_string).collect(),
         None => Vec::new(),
@@ -35,6 +36,28 @@ fn main() -> Result<()> {
     let mut runtime =
         Runtime::from_profile(path, &jobs).context(format!("Error loading profile at {}", path))?;
 
+    if let Some(grace_period) = matches.value_of("grace-period") {
+        let secs = grace_period
+            .parse::<f64>()
+            .context(format!("Invalid grace period \"{}\"", grace_period))?;
+        let grace_period = Duration::try_from_secs_f64(secs)
+            .context(format!("Invalid grace period \"{}\"", grace_period))?;
+
+        runtime = runtime.grace_period(grace_period);
+    }
+
+    if let Some(max_action_depth) = matches.value_of("max-action-depth") {
+        let max_action_depth = max_action_depth
+            .parse::<usize>()
+            .context(format!("Invalid max action depth \"{}\"", max_action_depth))?;
+
+        runtime = runtime.max_action_depth(max_action_depth);
+    }
+
+    if matches.is_present("strip-ansi") && !stdout().is_terminal() {
+        runtime = runtime.strip_ansi(true);
+    }
+
     if let Some(("bin", sub_matches)) = matches.subcommand() {
         let bin = sub_matches.value_of("BIN");
         let args = match sub_matches.values_of("args") {
@@ -47,5 +70,13 @@ fn main() -> Result<()> {
         }
     }
 
-    runtime.run()
+    if let Err(error) = runtime.run() {
+        if let Some(ExitError { code }) = error.downcast_ref::<ExitError>() {
+            std::process::exit(*code);
+        }
+
+        return Err(error);
+    }
+
+    Ok(())
 }
@@ -0,0 +1,60 @@
+use std::{borrow::Cow, iter::Peekable, ops::RangeInclusive, str::Chars};
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{07}';
+
+/// Removes ANSI escape sequences, such as colors and cursor movements, from a line of output.
+///
+/// Control sequences (`ESC [`), operating system commands (`ESC ]`), and other string sequences
+/// (`ESC P`, `ESC X`, `ESC ^`, `ESC _`) are removed along with any other two character escapes.
+pub fn strip(line: &str) -> Cow<'_, str> {
+    if !line.contains(ESC) {
+        return Cow::Borrowed(line);
+    }
+
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            stripped.push(c);
+
+            continue;
+        }
+
+        match chars.next() {
+            Some('[') => {
+                // Parameter and intermediate bytes, then a single final byte.
+                skip(&mut chars, '\u{20}'..='\u{3f}');
+                chars.next_if(|c| ('\u{40}'..='\u{7e}').contains(c));
+            }
+            Some(']' | 'P' | 'X' | '^' | '_') => {
+                // Strings are terminated by BEL or by the string terminator `ESC \`.
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+
+                    if c == ESC {
+                        chars.next_if_eq(&'\\');
+
+                        break;
+                    }
+                }
+            }
+            Some(c) if ('\u{20}'..='\u{2f}').contains(&c) => {
+                // Intermediate bytes, such as those selecting a character set, then a final byte.
+                skip(&mut chars, '\u{20}'..='\u{2f}');
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+
+    Cow::Owned(stripped)
+}
+
+/// Skips characters in the specified range.
+fn skip(chars: &mut Peekable<Chars>, range: RangeInclusive<char>) {
+    while chars.next_if(|c| range.contains(c)).is_some() {}
+}
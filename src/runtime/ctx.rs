@@ -1,18 +1,34 @@
 use crate::runtime::{
-    job::task::{log_monitor::LogMonitor, process::Process},
+    job::task::{control::TaskControl, log_monitor::LogMonitor, process::Process},
     local_bin::BinCommand,
+    registry::ProcessRegistry,
+    scope::Scope,
+    shutdown::Shutdown,
+    vars::Vars,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 /// Runtime context object.
 ///
-/// This object contains indexes to defined processes and log monitors as well as the current
-/// `BinCommand` object.
+/// This object contains indexes to defined processes and log monitors, the current `BinCommand`
+/// object, the registry of running children of the runtime or current task, the variables captured
+/// from process output, the scope from which processes are executed, the handle through which the
+/// current task is controlled, the token used to coordinate the shutdown of the runtime or current
+/// task, the grace period given to children to exit when the runtime shuts down, the maximum length
+/// of a chain of actions, and whether to strip ANSI escape sequences from logged process output.
 #[derive(Clone, Debug)]
 pub struct Ctx {
     pub bin_command: BinCommand,
+    pub control: TaskControl,
+    pub grace_period: Duration,
     pub log_monitor_map: HashMap<String, LogMonitor>,
+    pub max_action_depth: usize,
     pub process_map: HashMap<String, Process>,
+    pub registry: ProcessRegistry,
+    pub scope: Scope,
+    pub shutdown: Shutdown,
+    pub strip_ansi: bool,
+    pub vars: Vars,
 }
 
 impl Default for Ctx {
@@ -26,8 +42,16 @@ impl Ctx {
     pub fn new() -> Self {
         Self {
             bin_command: BinCommand::system_default(),
+            control: TaskControl::default(),
+            grace_period: Duration::from_secs(10),
             log_monitor_map: HashMap::new(),
+            max_action_depth: 1000,
             process_map: HashMap::new(),
+            registry: ProcessRegistry::new(),
+            scope: Scope::new(),
+            shutdown: Shutdown::new(),
+            strip_ansi: false,
+            vars: Vars::new(),
         }
     }
 }
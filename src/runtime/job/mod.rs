@@ -3,12 +3,14 @@ pub mod task;
 use crate::runtime::ctx::Ctx;
 use anyhow::Result;
 use log::debug;
-use task::Task;
+use std::{collections::HashMap, mem};
+use task::{Next, Task};
 
 /// Represents and contains a given runtime job.
 ///
 /// This object contains the job name as well as the tasks the job performs. When run, all defined
-/// tasks are executed in order.
+/// tasks are executed in order until the runtime shuts down. Processes which a task leaves running
+/// once they are ready keep running alongside later tasks, and the job completes once they exit.
 #[derive(Clone, Debug)]
 pub struct Job {
     pub name: String,
@@ -21,15 +23,64 @@ impl Job {
         Self { name, tasks }
     }
 
-    /// Executes defined tasks in order.
+    /// Executes defined tasks in order, then waits for any processes left running by them.
+    ///
+    /// A task may be restarted, or the job restarted from its first task, by a builtin action
+    /// performed while the task runs. Before the job is restarted, any processes left running by
+    /// its tasks are stopped.
     pub fn run(self, ctx: &Ctx) -> Result<()> {
         debug!(
             "Running job instance \"{}\" with structure:\n{:#?}",
             self.name, self
         );
 
-        self.tasks
-            .iter()
-            .try_for_each(|task| task.clone().run(&ctx.clone()))
+        let mut background_handles = Vec::new();
+        let mut completions = HashMap::new();
+
+        let runtime_shutdown = ctx.shutdown.clone();
+        let mut ctx = ctx.clone();
+        ctx.scope.job = self.name.clone();
+        ctx.shutdown = runtime_shutdown.child();
+
+        let mut index = 0;
+
+        let result = loop {
+            if index >= self.tasks.len() || ctx.shutdown.is_triggered() {
+                break Ok(());
+            }
+
+            let mut task_ctx = ctx.clone();
+            task_ctx.scope.task_index = index + 1;
+
+            match self.tasks[index].clone().run(&task_ctx, &mut completions) {
+                Ok((next, handles)) => {
+                    background_handles.extend(handles);
+
+                    index = match next {
+                        Next::Continue => index + 1,
+                        Next::RestartTask => {
+                            debug!("Restarting task {} of job \"{}\"", index + 1, self.name);
+
+                            index
+                        }
+                        Next::RestartJob => {
+                            debug!("Restarting job \"{}\"", self.name);
+
+                            ctx.shutdown.trigger(0);
+                            task::join(mem::take(&mut background_handles))?;
+                            completions.clear();
+                            ctx.shutdown = runtime_shutdown.child();
+
+                            0
+                        }
+                    };
+                }
+                Err(error) => break Err(error),
+            }
+        };
+
+        task::join(background_handles)?;
+
+        result
     }
 }
@@ -1,68 +1,257 @@
-use crate::runtime::{
-    ctx::Ctx,
-    job::task::{log_monitor::LogMonitor, process::Process},
-};
-use log::debug;
+use crate::runtime::{ctx::Ctx, job::task::control::TaskCmd, scope::Parent, signal};
+use anyhow::{anyhow, ensure, Context, Error, Result};
+use log::{debug, error, info};
+use std::{str::FromStr, thread};
 
-pub const BUILTIN_ACTIONS: [&str; 2] = ["arpx_exit", "arpx_exit_error"];
+/// Builtin action, which controls the runtime rather than executing a process.
+///
+/// `Exit` shuts down the runtime with the given exit status (`arpx_exit`, `arpx_exit_error`, or
+/// `arpx_exit(<code>)`), `Noop` does nothing, and `Signal` sends a signal to the running children
+/// of a process in the same task (`arpx_signal:<process>:<SIGNAL>`). The remaining actions
+/// control the task in which they are performed, as described by [`TaskCmd`].
+///
+/// [`TaskCmd`]: crate::runtime::job::task::control::TaskCmd
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Builtin {
+    Exit(i32),
+    KillTask,
+    Noop,
+    RestartJob,
+    RestartTask,
+    Signal { process: String, signal: i32 },
+    SkipTask,
+}
+
+impl FromStr for Builtin {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "arpx_exit" => Ok(Self::Exit(0)),
+            "arpx_exit_error" => Ok(Self::Exit(1)),
+            "arpx_kill_task" => Ok(Self::KillTask),
+            "arpx_noop" => Ok(Self::Noop),
+            "arpx_restart_job" => Ok(Self::RestartJob),
+            "arpx_restart_task" => Ok(Self::RestartTask),
+            "arpx_skip_task" => Ok(Self::SkipTask),
+            _ if s.starts_with("arpx_signal:") => {
+                let (process, name) =
+                    s["arpx_signal:".len()..].split_once(':').context(format!(
+                        "Invalid signal action \"{}\": expected arpx_signal:<process>:<SIGNAL>",
+                        s
+                    ))?;
+                let signal =
+                    signal::from_name(name).context(format!("Invalid signal \"{}\"", name))?;
+
+                Ok(Self::Signal {
+                    process: process.to_owned(),
+                    signal,
+                })
+            }
+            _ => {
+                let code = s
+                    .strip_prefix("arpx_exit(")
+                    .and_then(|s| s.strip_suffix(')'))
+                    .context(format!("Unknown builtin action \"{}\"", s))?;
+                let code = code.trim().parse::<u8>().context(format!(
+                    "Invalid exit status \"{}\": expected a number from 0 to 255",
+                    code
+                ))?;
+
+                Ok(Self::Exit(code.into()))
+            }
+        }
+    }
+}
 
-pub struct ProcessActions {
-    pub onfail: OptionalAction,
-    pub onsucceed: OptionalAction,
+/// An action pending execution, along with the process whose exit or log monitor trigger caused
+/// it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Action {
+    pub name: String,
+    pub parent: Parent,
 }
 
-pub type OptionalAction = Option<Box<dyn Fn() + Send>>;
+impl Action {
+    /// Constructs a new `Action` with the specified name, caused by the specified parent.
+    pub fn new(name: String, parent: Parent) -> Self {
+        Self { name, parent }
+    }
+}
 
-pub fn get_process_actions(process: &Process, ctx: &Ctx) -> ProcessActions {
-    let onfail = match &process.onfail {
-        Some(action_name) => get_optional_action(action_name.into(), ctx.clone()),
-        None => None,
-    };
-    let onsucceed = match &process.onsucceed {
-        Some(action_name) => get_optional_action(action_name.into(), ctx.clone()),
-        None => None,
+/// Performs the specified actions, in order on the current thread or concurrently, along with any
+/// actions which they cause in turn.
+///
+/// Actions are queued and executed iteratively rather than recursively, so that chains of actions,
+/// such as a process which is recovered by another process which then executes it again, do not
+/// grow the thread stack. If a chain grows longer than the maximum action depth of the runtime,
+/// an error is logged and the runtime shuts down with an error status.
+pub fn perform(actions: Vec<Action>, concurrent: bool, ctx: &Ctx) {
+    let result = match concurrent {
+        true => execute_concurrently(actions, 1, ctx),
+        false => execute(actions, 1, ctx),
     };
 
-    ProcessActions { onfail, onsucceed }
-}
+    if let Err(error) = result {
+        error!("{:#}", error);
 
-pub fn get_log_monitor_action(log_monitor: &LogMonitor, ctx: &Ctx) -> OptionalAction {
-    get_optional_action(log_monitor.ontrigger.clone(), ctx.clone())
+        ctx.shutdown.root().trigger(1);
+    }
 }
 
-fn get_optional_action(action_name: String, ctx: Ctx) -> OptionalAction {
-    if BUILTIN_ACTIONS.contains(&&action_name[..]) {
-        return Some(Box::new(move || execute_action(&action_name[..])));
+/// Executes each of the specified actions, along with the actions which it causes, on its own
+/// thread, and waits for all of them to complete.
+fn execute_concurrently(actions: Vec<Action>, depth: usize, ctx: &Ctx) -> Result<()> {
+    if actions.len() < 2 {
+        return execute(actions, depth, ctx);
     }
 
-    match ctx.clone().process_map.get(&action_name[..]) {
-        Some(process) => {
-            let cloned_process = process.clone();
+    let name = thread::current().name().unwrap_or_default().to_owned();
+
+    thread::scope(|scope| {
+        let handles = actions
+            .into_iter()
+            .map(|action| {
+                thread::Builder::new()
+                    .name(name.clone())
+                    .spawn_scoped(scope, move || execute(vec![action], depth, ctx))
+            })
+            .collect::<Vec<_>>();
+
+        handles.into_iter().try_for_each(|handle| {
+            handle?
+                .join()
+                .map_err(|_| anyhow!("Error joining action thread"))?
+        })
+    })
+}
 
-            Some(Box::new(move || {
-                let process_actions = get_process_actions(&cloned_process, &ctx);
+fn execute(actions: Vec<Action>, depth: usize, ctx: &Ctx) -> Result<()> {
+    let mut stack = actions
+        .into_iter()
+        .rev()
+        .map(|action| (action, depth))
+        .collect::<Vec<(Action, usize)>>();
 
-                cloned_process.run(process_actions, &ctx, &[]).ok();
-            }))
+    while let Some((action, depth)) = stack.pop() {
+        if ctx.shutdown.is_triggered() {
+            debug!(
+                "Runtime is shutting down, skipping action \"{}\"",
+                action.name
+            );
+
+            return Ok(());
+        }
+
+        ensure!(
+            depth <= ctx.max_action_depth,
+            "Action \"{}\" on process \"{}\" exceeded the maximum action depth of {}",
+            action.name,
+            action.parent.process,
+            ctx.max_action_depth
+        );
+
+        if let Ok(builtin) = action.name.parse::<Builtin>() {
+            match execute_builtin(builtin, &action.parent, ctx) {
+                true => continue,
+                false => return Ok(()),
+            }
+        }
+
+        let process = match ctx.process_map.get(&action.name[..]) {
+            Some(process) => process,
+            None => {
+                debug!(
+                    "Unknown action \"{}\" received. Doing nothing.",
+                    action.name
+                );
+
+                continue;
+            }
+        };
+
+        let mut action_ctx = ctx.clone();
+        action_ctx.scope.parent = Some(action.parent);
+
+        match process.run(&action_ctx, &[], None) {
+            Ok(outcome) if outcome.concurrent => {
+                execute_concurrently(outcome.actions, depth + 1, ctx)?
+            }
+            Ok(outcome) => stack.extend(
+                outcome
+                    .actions
+                    .into_iter()
+                    .rev()
+                    .map(|action| (action, depth + 1)),
+            ),
+            Err(error) => error!("{:#}", error),
         }
-        None => None,
     }
+
+    Ok(())
 }
 
-fn execute_action(action: &str) {
-    match action {
-        "arpx_exit" => {
-            debug!("Received builtin action \"arpx_exit\". Exiting runtime.");
-            std::process::exit(0)
-        }
-        "arpx_exit_error" => {
+/// Executes the specified builtin action. Returns whether the actions which follow it should
+/// still be performed, which is not the case once the task or runtime is being stopped.
+fn execute_builtin(builtin: Builtin, parent: &Parent, ctx: &Ctx) -> bool {
+    let cmd = match builtin {
+        Builtin::Exit(code) => {
             debug!(
-                "Received builtin action \"arpx_exit_error\". Exiting runtime with error status."
+                "Received builtin action to exit with status {}. Shutting down runtime.",
+                code
             );
-            std::process::exit(1)
+            ctx.shutdown.root().trigger(code);
+
+            return false;
+        }
+        Builtin::Noop => return true,
+        Builtin::Signal { process, signal } => {
+            signal_process(&process, signal, parent, ctx);
+
+            return true;
         }
-        _ => {
-            debug!("Unknown builtin action received. Doing nothing.");
+        Builtin::KillTask => TaskCmd::Kill,
+        Builtin::RestartJob => TaskCmd::RestartJob,
+        Builtin::RestartTask => TaskCmd::Restart,
+        Builtin::SkipTask => TaskCmd::Skip,
+    };
+
+    debug!(
+        "Received builtin action {:?} from \"{}\". Sending {:?} to task.",
+        builtin, parent.process, cmd
+    );
+
+    if !ctx.control.send(cmd) {
+        debug!("Task is no longer running. Doing nothing.");
+    }
+
+    cmd == TaskCmd::Skip
+}
+
+/// Sends the specified signal to the running children of the specified process in the current
+/// task, along with any children they have spawned.
+fn signal_process(process: &str, signal: i32, parent: &Parent, ctx: &Ctx) {
+    let pids = ctx.registry.pids_of(process);
+    let signal_name = signal::name(signal).unwrap_or("signal");
+
+    if pids.is_empty() {
+        debug!(
+            "Received builtin action to send {} to \"{}\", which is not running in the current task. Doing nothing.",
+            signal_name, process
+        );
+    }
+
+    for pid in pids {
+        info!(
+            "Sending {} to \"{}\" ({}) on behalf of \"{}\"",
+            signal_name, process, pid, parent.process
+        );
+
+        if let Err(error) = signal::send_to_group(pid, signal) {
+            debug!(
+                "Error sending {} to \"{}\" ({}): {}",
+                signal_name, process, pid, error
+            );
         }
     }
 }
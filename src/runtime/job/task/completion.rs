@@ -0,0 +1,52 @@
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// Outcome of a process run by a job, shared with the processes which depend on it.
+///
+/// A completion is settled once, either when the process becomes ready or when it exits, and
+/// records whether the process was ready or succeeded. Later attempts to settle it are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Completion {
+    state: Arc<(Mutex<Option<bool>>, Condvar)>,
+}
+
+impl Completion {
+    /// Constructs a new, unsettled `Completion`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Settles the completion with whether the process was ready or succeeded.
+    pub fn complete(&self, succeeded: bool) {
+        let mut outcome = self.lock();
+
+        if outcome.is_none() {
+            *outcome = Some(succeeded);
+            self.state.1.notify_all();
+        }
+    }
+
+    /// Blocks until the completion is settled. Returns whether the process was ready or
+    /// succeeded.
+    pub fn wait(&self) -> bool {
+        let mut outcome = self.lock();
+
+        loop {
+            if let Some(succeeded) = *outcome {
+                return succeeded;
+            }
+
+            outcome = self
+                .state
+                .1
+                .wait(outcome)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<bool>> {
+        self.state
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,42 @@
+use crossbeam_channel::{unbounded, Receiver, Sender};
+
+/// Command sent by a builtin action to the task in which it is performed.
+///
+/// `Kill` stops the processes of the task, after which the job continues to its next task.
+/// `Restart` and `RestartJob` stop the processes of the task and then run it, or the whole job,
+/// again. `Skip` continues the job to its next task without waiting for the processes of the task,
+/// which keep running alongside later tasks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TaskCmd {
+    Kill,
+    Restart,
+    RestartJob,
+    Skip,
+}
+
+/// Handle through which commands are sent to the task which is currently running.
+#[derive(Clone, Debug)]
+pub struct TaskControl {
+    sender: Sender<TaskCmd>,
+}
+
+impl Default for TaskControl {
+    fn default() -> Self {
+        Self::new().0
+    }
+}
+
+impl TaskControl {
+    /// Constructs a new `TaskControl`, along with the receiver on which its commands arrive.
+    pub fn new() -> (Self, Receiver<TaskCmd>) {
+        let (sender, receiver) = unbounded();
+
+        (Self { sender }, receiver)
+    }
+
+    /// Sends the specified command to the task. Returns whether the task is still running to
+    /// receive it.
+    pub fn send(&self, cmd: TaskCmd) -> bool {
+        self.sender.send(cmd).is_ok()
+    }
+}
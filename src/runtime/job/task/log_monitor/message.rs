@@ -5,10 +5,28 @@ pub enum LogMonitorCmd {
     None,
 }
 
+/// Output stream of a process from which a line was read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl OutputStream {
+    /// Returns the name of the stream, as exposed to log monitor tests.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Stdout => "stdout",
+            Self::Stderr => "stderr",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct LogMonitorMessage {
     pub cmd: LogMonitorCmd,
     pub message: String,
+    pub stream: OutputStream,
 }
 
 impl Default for LogMonitorMessage {
@@ -22,6 +40,7 @@ impl LogMonitorMessage {
         Self {
             cmd: LogMonitorCmd::None,
             message: "Empty message.".to_owned(),
+            stream: OutputStream::Stdout,
         }
     }
 
@@ -36,4 +55,10 @@ impl LogMonitorMessage {
 
         self
     }
+
+    pub fn stream(mut self, s: OutputStream) -> Self {
+        self.stream = s;
+
+        self
+    }
 }
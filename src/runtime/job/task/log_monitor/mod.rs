@@ -1,28 +1,44 @@
 pub mod message;
 pub mod rolling_buffer;
 
-use crate::runtime::{ctx::Ctx, job::task::action::OptionalAction};
+use crate::runtime::{
+    ansi,
+    ctx::Ctx,
+    job::task::action::{self, Action},
+    local_bin::BinCommand,
+    scope::Parent,
+};
 use anyhow::{Context, Result};
 use crossbeam_channel::{unbounded, Sender};
 use log::debug;
-use message::{LogMonitorCmd, LogMonitorMessage};
+use message::{LogMonitorCmd, LogMonitorMessage, OutputStream};
 use rolling_buffer::RollingBuffer;
-use std::process::{Command, Stdio};
-use std::thread;
+use std::{
+    collections::HashMap,
+    process::{Command, Stdio},
+    thread,
+};
 
 /// Represents and contains a given runtime job task log monitor.
 ///
 /// This object contains all of the data necessary to run a given log monitor. This data includes
 /// the log monitor name, the size of its rolling buffer, the rolling buffer instance itself, the
-/// `test` command which should be executed on each push to the buffer, and the `ontrigger` action
-/// which should run if `test` returns with a `0` exit code.
+/// output stream to monitor, whether to strip ANSI escape sequences from output, the `test` command
+/// which should be executed on each push to the buffer, the `BinCommand` with which to execute the
+/// `test` command, if it differs from that of the runtime, any environment variables to set for the
+/// `test` command, and the `ontrigger` actions which should run, in order or concurrently, if
+/// `test` returns with a `0` exit code.
 #[derive(Clone, Debug)]
 pub struct LogMonitor {
     pub buffer: RollingBuffer,
+    pub bin_command: Option<BinCommand>,
     pub buffer_size: usize,
-    pub ctx: Ctx,
+    pub concurrent_actions: bool,
+    pub env: HashMap<String, String>,
     pub name: String,
-    pub ontrigger: String,
+    pub ontrigger: Vec<String>,
+    pub stream: Option<OutputStream>,
+    pub strip_ansi: bool,
     pub test: String,
 }
 
@@ -31,14 +47,27 @@ impl LogMonitor {
     pub fn new(name: String) -> Self {
         Self {
             buffer: RollingBuffer::new(20),
+            bin_command: None,
             buffer_size: 20,
-            ctx: Ctx::new(),
+            concurrent_actions: false,
+            env: HashMap::new(),
             name,
-            ontrigger: String::new(),
+            ontrigger: Vec::new(),
+            stream: None,
+            strip_ansi: true,
             test: String::new(),
         }
     }
 
+    /// Builds `LogMonitor` with the `BinCommand` used to execute `test`.
+    ///
+    /// If not specified, the runtime `BinCommand` is used.
+    pub fn bin_command(mut self, c: Option<BinCommand>) -> Self {
+        self.bin_command = c;
+
+        self
+    }
+
     /// Builds `LogMonitor` with the specified rolling buffer size.
     pub fn buffer_size(mut self, b: usize) -> Self {
         self.buffer = RollingBuffer::new(b);
@@ -47,13 +76,49 @@ impl LogMonitor {
         self
     }
 
-    /// Builds `LogMonitor` with the name of the action to execute if the `test` succeeds.
-    pub fn ontrigger(mut self, o: String) -> Self {
+    /// Builds `LogMonitor` with whether to perform the `ontrigger` actions concurrently, rather
+    /// than in order.
+    pub fn concurrent_actions(mut self, c: bool) -> Self {
+        self.concurrent_actions = c;
+
+        self
+    }
+
+    /// Builds `LogMonitor` with the specified environment variables.
+    ///
+    /// These variables are set on `test` in addition to `ARPX_BUFFER`, `ARPX_STREAM`, those
+    /// describing the scope of the monitored process, those captured by the runtime, and those
+    /// inherited from the runtime.
+    pub fn env(mut self, e: HashMap<String, String>) -> Self {
+        self.env = e;
+
+        self
+    }
+
+    /// Builds `LogMonitor` with the names of the actions to execute if the `test` succeeds.
+    pub fn ontrigger(mut self, o: Vec<String>) -> Self {
         self.ontrigger = o;
 
         self
     }
 
+    /// Builds `LogMonitor` with the output stream to monitor.
+    ///
+    /// If no stream is specified, both stdout and stderr are monitored.
+    pub fn stream(mut self, s: Option<OutputStream>) -> Self {
+        self.stream = s;
+
+        self
+    }
+
+    /// Builds `LogMonitor` with whether to strip ANSI escape sequences from lines pushed to the
+    /// rolling buffer. Defaults to `true`.
+    pub fn strip_ansi(mut self, s: bool) -> Self {
+        self.strip_ansi = s;
+
+        self
+    }
+
     /// Builds `LogMonitor` with the specified test.
     pub fn test(mut self, t: String) -> Self {
         self.test = t;
@@ -61,14 +126,21 @@ impl LogMonitor {
         self
     }
 
-    /// Executes the log monitor using the provided action.
+    /// Executes the log monitor on the output of the specified process using the provided context.
     pub fn run(
         mut self,
-        ontrigger: OptionalAction,
+        process: &str,
+        ctx: &Ctx,
     ) -> Result<(thread::JoinHandle<()>, Sender<LogMonitorMessage>)> {
         debug!("Running log_monitor instance with structure:\n{:#?}", self);
 
         let name = self.name.clone();
+        let bin_command = self
+            .bin_command
+            .clone()
+            .unwrap_or_else(|| ctx.bin_command.clone());
+        let process = process.to_owned();
+        let ctx = ctx.clone();
 
         let (sender, receiver) = unbounded::<LogMonitorMessage>();
 
@@ -78,7 +150,12 @@ impl LogMonitor {
                 debug!("Spawned log_monitor thread \"{}\"", &name);
 
                 loop {
-                    if let Ok(LogMonitorMessage { cmd, message }) = receiver.recv() {
+                    if let Ok(LogMonitorMessage {
+                        cmd,
+                        message,
+                        stream,
+                    }) = receiver.recv()
+                    {
                         debug!("Received message: {:?}", message);
 
                         match cmd {
@@ -86,8 +163,11 @@ impl LogMonitor {
                                 debug!("Received close message.");
                                 break;
                             }
+                            LogMonitorCmd::Log if self.stream.is_none_or(|s| s == stream) => {
+                                self.push(message, stream, &bin_command, &process, &ctx);
+                            }
                             LogMonitorCmd::Log => {
-                                self.push(message, &ontrigger);
+                                debug!("Ignored message from {}.", stream.name());
                             }
                             LogMonitorCmd::None => debug!("Received empty message."),
                         }
@@ -101,22 +181,47 @@ impl LogMonitor {
         Ok((handle, sender))
     }
 
-    /// Pushes a line of text to the rolling buffer and executes the test command on the new buffer
-    /// state.
-    pub fn push(&mut self, line: String, ontrigger: &OptionalAction) {
-        self.buffer.push(line);
-        self.exec_test(ontrigger).ok();
+    /// Pushes a line of text read from the specified stream of the specified process to the
+    /// rolling buffer and executes the test command on the new buffer state using the specified
+    /// `BinCommand` and context.
+    pub fn push(
+        &mut self,
+        line: String,
+        stream: OutputStream,
+        bin_command: &BinCommand,
+        process: &str,
+        ctx: &Ctx,
+    ) {
+        self.buffer.push(match self.strip_ansi {
+            true => ansi::strip(&line).into_owned(),
+            false => line,
+        });
+        self.exec_test(stream, bin_command, process, ctx).ok();
     }
 
-    /// Executes the current test command and, if successful, performs the `ontrigger` action.
-    pub fn exec_test(&self, ontrigger: &OptionalAction) -> Result<()> {
-        let bin = self.ctx.bin_command.bin.clone();
-        let mut bin_args = self.ctx.bin_command.args.clone();
+    /// Executes the current test command on behalf of the specified process using the specified
+    /// `BinCommand` and context and, if successful, performs the `ontrigger` action. The stream
+    /// from which the latest line was read is exposed to the test as `ARPX_STREAM`, and the buffer
+    /// which triggered the action is exposed to the action as `ARPX_TRIGGER_BUFFER`.
+    pub fn exec_test(
+        &self,
+        stream: OutputStream,
+        bin_command: &BinCommand,
+        process: &str,
+        ctx: &Ctx,
+    ) -> Result<()> {
+        let buffer = self.buffer.dump();
+        let bin = bin_command.bin.clone();
+        let mut bin_args = bin_command.args.clone();
         bin_args.push(self.test.clone());
 
         let status = Command::new(bin)
             .args(bin_args)
-            .env("ARPX_BUFFER", &self.buffer.dump()[..])
+            .envs(ctx.vars.env())
+            .envs(&self.env)
+            .envs(ctx.scope.env(process))
+            .env("ARPX_BUFFER", &buffer[..])
+            .env("ARPX_STREAM", stream.name())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -134,14 +239,22 @@ impl LogMonitor {
         if status.success() {
             debug!("LogMonitor {} triggered", self.name);
 
-            if let Some(action) = ontrigger {
-                debug!(
-                    "Running ontrigger \"{}\" from prepared action",
-                    self.ontrigger
-                );
+            let parent = Parent {
+                exit_code: None,
+                process: process.to_owned(),
+                trigger_buffer: Some(buffer),
+            };
+            let actions = self
+                .ontrigger
+                .iter()
+                .map(|name| {
+                    debug!("Performing ontrigger \"{}\"", name);
+
+                    Action::new(name.clone(), parent.clone())
+                })
+                .collect();
 
-                action();
-            }
+            action::perform(actions, self.concurrent_actions, ctx);
         }
 
         Ok(())
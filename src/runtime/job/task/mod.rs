@@ -1,13 +1,26 @@
 pub mod action;
+pub mod completion;
+pub mod control;
 pub mod log_monitor;
 pub mod process;
 
 use crate::runtime::ctx::Ctx;
-use action::{get_log_monitor_action, get_process_actions};
 use anyhow::{bail, Error, Result};
-use log::debug;
-use process::Process;
-use std::thread;
+use completion::Completion;
+use control::{TaskCmd, TaskControl};
+use crossbeam_channel::{bounded, select};
+use log::{debug, error, info};
+use log_monitor::message::{LogMonitorCmd, LogMonitorMessage};
+use process::{Outcome, Process};
+use std::{collections::HashMap, thread};
+
+/// What a job should do once a task has run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Next {
+    Continue,
+    RestartTask,
+    RestartJob,
+}
 
 /// Represents and contains a given runtime job task.
 ///
@@ -24,47 +37,202 @@ impl Task {
     }
 
     /// Executes defined processes in order.
-    pub fn run(self, ctx: &Ctx) -> Result<()> {
+    ///
+    /// Each process first waits for its dependencies which have been run by this task or by an
+    /// earlier task in the job, as recorded in `completions`. A dependency is satisfied once it
+    /// succeeds or, if it has a readiness probe, once it is ready. If a dependency is not
+    /// satisfied, the process is skipped.
+    ///
+    /// The task completes once every process has exited, except for processes with a readiness
+    /// probe, which only need to become ready. The thread handles of such processes, which may
+    /// still be running, are returned so that they can be joined later.
+    ///
+    /// While the task runs, it receives commands from builtin actions performed by its processes
+    /// and log monitors. The processes of the task are stopped if it is killed or restarted, and
+    /// are all left running if it is skipped. Returns what the job should do next.
+    pub fn run(
+        self,
+        ctx: &Ctx,
+        completions: &mut HashMap<String, Completion>,
+    ) -> Result<(Next, Vec<thread::JoinHandle<()>>)> {
         debug!("Running task instance with structure:\n{:#?}", self);
 
+        let (control, commands) = TaskControl::new();
+        let ctx = &Ctx {
+            control,
+            registry: ctx.registry.child(),
+            shutdown: ctx.shutdown.child(),
+            ..ctx.clone()
+        };
+        let (done, all_done) = bounded::<()>(0);
         let mut thread_handles = Vec::new();
+        let mut ready_processes = Vec::new();
+
+        for process in &self.processes {
+            completions.insert(process.name.clone(), Completion::new());
+        }
 
         for process in self.processes {
+            let mut process_handles = Vec::new();
             let mut log_monitor_senders = Vec::new();
             for log_monitor_name in &process.log_monitors {
                 let log_monitor = &ctx.log_monitor_map[log_monitor_name];
 
-                let log_monitor_action = get_log_monitor_action(log_monitor, ctx);
-                let (handle, sender) = log_monitor.clone().run(log_monitor_action)?;
+                let (handle, sender) = log_monitor.clone().run(&process.name, ctx)?;
 
-                thread_handles.push(handle);
+                process_handles.push(handle);
                 log_monitor_senders.push(sender);
             }
 
+            let completion = completions[&process.name].clone();
+            let process_has_readiness = process.ready_when.is_some();
+            let dependencies = process
+                .depends_on
+                .iter()
+                .filter_map(|name| {
+                    completions
+                        .get(name)
+                        .map(|completion| (name.clone(), completion.clone()))
+                })
+                .collect::<Vec<(String, Completion)>>();
+            let cloned_completion = completion.clone();
             let cloned_ctx = ctx.clone();
+            let cloned_done = (!process_has_readiness).then(|| done.clone());
+            let process_name = process.name.clone();
             let process_handle = thread::Builder::new()
                 .name(process.name.clone())
                 .spawn(move || {
+                    let _done = cloned_done;
+
                     debug!("Spawned thread \"{}\"", process.name);
 
-                    let process_actions = get_process_actions(&process, &cloned_ctx);
-                    process
-                        .run(process_actions, &cloned_ctx, &log_monitor_senders)
-                        .ok();
+                    for (name, dependency) in &dependencies {
+                        if !dependency.wait() {
+                            if !cloned_ctx.shutdown.is_triggered() {
+                                info!(
+                                    "\"{}\" skipped, dependency \"{}\" did not succeed",
+                                    process.name, name
+                                );
+                            }
+
+                            cloned_completion.complete(false);
+
+                            for sender in log_monitor_senders.iter() {
+                                sender
+                                    .send(LogMonitorMessage::new().cmd(LogMonitorCmd::Close))
+                                    .ok();
+                            }
+
+                            return;
+                        }
+                    }
+
+                    let outcome = process
+                        .run(
+                            &cloned_ctx,
+                            &log_monitor_senders,
+                            Some(cloned_completion.clone()),
+                        )
+                        .unwrap_or_else(|error| {
+                            error!("{:#}", error);
+
+                            Outcome::default()
+                        });
+
+                    action::perform(outcome.actions, outcome.concurrent, &cloned_ctx);
+
+                    cloned_completion.complete(outcome.succeeded);
 
                     debug!("Closing thread \"{}\"", process.name);
                 })
                 .map_err(Error::new)?;
 
-            thread_handles.push(process_handle);
+            process_handles.push(process_handle);
+
+            if process_has_readiness {
+                let cloned_completion = completion.clone();
+                let cloned_done = done.clone();
+
+                process_handles.push(
+                    thread::Builder::new()
+                        .name(process_name)
+                        .spawn(move || {
+                            cloned_completion.wait();
+
+                            drop(cloned_done);
+                        })
+                        .map_err(Error::new)?,
+                );
+            }
+
+            match process_has_readiness {
+                true => ready_processes.push((completion, process_handles)),
+                false => thread_handles.extend(process_handles),
+            }
         }
 
-        for handle in thread_handles {
-            if handle.join().is_err() {
-                bail!("Error joining thread handle");
+        drop(done);
+
+        let mut next = Next::Continue;
+
+        loop {
+            select! {
+                recv(all_done) -> _ => break,
+                recv(commands) -> cmd => match cmd {
+                    Ok(TaskCmd::Skip) if !ctx.shutdown.is_triggered() => {
+                        debug!("Skipping task, leaving its processes running");
+
+                        thread_handles.extend(
+                            ready_processes
+                                .into_iter()
+                                .flat_map(|(_, process_handles)| process_handles),
+                        );
+
+                        return Ok((Next::Continue, thread_handles));
+                    }
+                    Ok(cmd) if !ctx.shutdown.is_triggered() => {
+                        debug!("Stopping task on {:?}", cmd);
+
+                        next = match cmd {
+                            TaskCmd::Restart => Next::RestartTask,
+                            TaskCmd::RestartJob => Next::RestartJob,
+                            _ => Next::Continue,
+                        };
+
+                        ctx.shutdown.trigger(0);
+                    }
+                    _ => debug!("Task is already stopping, ignoring {:?}", cmd),
+                },
             }
         }
 
-        Ok(())
+        let mut background_handles = Vec::new();
+
+        for (completion, process_handles) in ready_processes {
+            completion.wait();
+
+            background_handles.extend(process_handles);
+        }
+
+        join(thread_handles)?;
+
+        if next != Next::Continue {
+            join(background_handles)?;
+
+            return Ok((next, Vec::new()));
+        }
+
+        Ok((next, background_handles))
     }
 }
+
+/// Joins the specified thread handles.
+pub fn join(thread_handles: Vec<thread::JoinHandle<()>>) -> Result<()> {
+    for handle in thread_handles {
+        if handle.join().is_err() {
+            bail!("Error joining thread handle");
+        }
+    }
+
+    Ok(())
+}
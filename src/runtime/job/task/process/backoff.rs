@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+/// Strategy used to space out repeated attempts of a process.
+///
+/// `Fixed` waits the same base delay between every attempt. `Exponential` doubles the base delay
+/// after each attempt, never waiting longer than `cap` if one is provided.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Backoff {
+    #[default]
+    Fixed,
+    Exponential {
+        cap: Option<Duration>,
+    },
+}
+
+impl Backoff {
+    /// Returns the delay to wait after the specified attempt, starting from 1.
+    pub fn delay(&self, base: Duration, attempt: u32) -> Duration {
+        match self {
+            Self::Fixed => base,
+            Self::Exponential { cap } => {
+                let delay = 2u32
+                    .checked_pow(attempt.saturating_sub(1))
+                    .and_then(|factor| base.checked_mul(factor))
+                    .unwrap_or(Duration::MAX);
+
+                match cap {
+                    Some(cap) => delay.min(*cap),
+                    None => delay,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use regex::Regex;
+
+/// Part of the stdout of a process `command` which is captured.
+///
+/// `Stdout` captures all of stdout, `LastLine` the last line which is not empty, and `Regex` the
+/// first capture group, or else the whole match, of the first line matching the given regular
+/// expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaptureMode {
+    Stdout,
+    LastLine,
+    Regex(String),
+}
+
+/// Capture of the stdout of a process `command` into a runtime variable.
+///
+/// The variable is only set if the `command` succeeds and, in `Regex` mode, if a line matches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capture {
+    pub mode: CaptureMode,
+    pub var: String,
+}
+
+impl Capture {
+    /// Constructs a new `Capture` into the specified variable using the specified mode.
+    pub fn new(var: String, mode: CaptureMode) -> Self {
+        Self { mode, var }
+    }
+
+    /// Begins capturing a single attempt of the `command`.
+    pub fn capturer(&self) -> Result<Capturer> {
+        Ok(Capturer {
+            mode: self.mode.clone(),
+            pattern: match &self.mode {
+                CaptureMode::Regex(pattern) => Some(Regex::new(pattern)?),
+                _ => None,
+            },
+            value: None,
+        })
+    }
+}
+
+/// Collector of the stdout of a single attempt of a `command`.
+#[derive(Debug)]
+pub struct Capturer {
+    mode: CaptureMode,
+    pattern: Option<Regex>,
+    value: Option<String>,
+}
+
+impl Capturer {
+    /// Collects a line of stdout.
+    pub fn push(&mut self, line: &str) {
+        match (&self.mode, &self.pattern) {
+            (CaptureMode::Stdout, _) => match &mut self.value {
+                Some(value) => {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+                None => self.value = Some(line.to_owned()),
+            },
+            (CaptureMode::LastLine, _) if !line.trim().is_empty() => {
+                self.value = Some(line.to_owned());
+            }
+            (CaptureMode::Regex(_), Some(pattern)) if self.value.is_none() => {
+                self.value = pattern.captures(line).and_then(|captures| {
+                    captures
+                        .get(1)
+                        .or_else(|| captures.get(0))
+                        .map(|capture| capture.as_str().to_owned())
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the captured value, if any.
+    pub fn finish(self) -> Option<String> {
+        match self.mode {
+            CaptureMode::Stdout => Some(self.value.unwrap_or_default()),
+            _ => self.value,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+/// Encoding in which a process `command` writes its output.
+///
+/// `Utf8` decodes output as UTF-8, replacing any invalid sequences with `U+FFFD`, and `Latin1`
+/// decodes each byte of output as the character with the same code point, so that no output is
+/// ever lost.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Latin1,
+}
+
+impl Encoding {
+    /// Decodes a line of output.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Latin1 => bytes.iter().map(|byte| char::from(*byte)).collect(),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::runtime::signal;
+use anyhow::{bail, ensure, Context, Error, Result};
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
+use std::{process::ExitStatus, str::FromStr};
+
+/// Condition against which the exit status of a process is matched to select an `onexit` action.
+///
+/// `Code` matches a single exit code, `Range` matches any exit code within an inclusive range, and
+/// `Signal` matches a process which was terminated by the given signal.
+///
+/// Conditions parse from strings such as `2`, `64-78`, and `SIGTERM`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExitCondition {
+    Code(i32),
+    Range(i32, i32),
+    Signal(i32),
+}
+
+impl ExitCondition {
+    /// Returns whether the specified exit status satisfies this condition.
+    pub fn matches(&self, status: &ExitStatus) -> bool {
+        match self {
+            Self::Code(code) => status.code() == Some(*code),
+            Self::Range(start, end) => status
+                .code()
+                .is_some_and(|code| (*start..=*end).contains(&code)),
+            Self::Signal(signal) => terminating_signal(status) == Some(*signal),
+        }
+    }
+
+    /// Returns the index of the most specific condition matching the specified exit status.
+    ///
+    /// Exact codes and signals take precedence over ranges, and narrower ranges take precedence
+    /// over wider ones. Of ranges with the same width, the one which starts lowest is chosen.
+    pub fn best_match<'a>(
+        conditions: impl Iterator<Item = &'a ExitCondition>,
+        status: &ExitStatus,
+    ) -> Option<usize> {
+        conditions
+            .enumerate()
+            .filter(|(_, condition)| condition.matches(status))
+            .min_by_key(|(_, condition)| match condition {
+                Self::Range(start, end) => (i64::from(*end) - i64::from(*start) + 1, *start),
+                Self::Code(_) | Self::Signal(_) => (0, 0),
+            })
+            .map(|(i, _)| i)
+    }
+}
+
+impl FromStr for ExitCondition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+
+        if let Ok(code) = s.parse::<i32>() {
+            return Ok(Self::Code(code));
+        }
+
+        if let Some((start, end)) = s.split_once('-') {
+            if let (Ok(start), Ok(end)) = (start.trim().parse::<i32>(), end.trim().parse::<i32>()) {
+                ensure!(
+                    start <= end,
+                    "Invalid exit code range \"{}\": start is greater than end",
+                    s
+                );
+
+                return Ok(Self::Range(start, end));
+            }
+        }
+
+        if s.is_empty() {
+            bail!("Invalid exit condition: empty");
+        }
+
+        signal::from_name(s).map(Self::Signal).context(format!(
+            "Invalid exit condition \"{}\": expected an exit code, a range of exit codes, or a signal name",
+            s
+        ))
+    }
+}
+
+/// Returns the signal which terminated the process with the specified exit status, if any.
+#[cfg(unix)]
+pub fn terminating_signal(status: &ExitStatus) -> Option<i32> {
+    status.signal()
+}
+
+#[cfg(not(unix))]
+pub fn terminating_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Returns the exit status given to a process which could not be spawned, which has the exit code
+/// a shell reports for a command that cannot be found or executed.
+#[cfg(unix)]
+pub fn spawn_failure() -> ExitStatus {
+    ExitStatus::from_raw(127 << 8)
+}
+
+#[cfg(not(unix))]
+pub fn spawn_failure() -> ExitStatus {
+    #[cfg(windows)]
+    return std::os::windows::process::ExitStatusExt::from_raw(127);
+
+    // Targets without process support have no exit status which can be constructed.
+    #[cfg(not(windows))]
+    unimplemented!("Processes are not supported on this platform")
+}
@@ -0,0 +1,46 @@
+/// Expands environment variables in an argument of a directly executed `command`.
+///
+/// Both `$NAME` and `${NAME}` are replaced with the value returned by `lookup`, or with nothing if
+/// the variable is not set, and `$$` is replaced with a literal `$`. A `$` which does not begin a
+/// variable is kept as is.
+pub fn expand_env(arg: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut expanded = String::with_capacity(arg.len());
+    let mut rest = arg;
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            expanded.push('$');
+            rest = after;
+
+            continue;
+        }
+
+        let (name, after) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", rest),
+            },
+            None => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+
+                (&rest[..end], &rest[end..])
+            }
+        };
+
+        match name {
+            "" => expanded.push('$'),
+            name => expanded.push_str(&lookup(name).unwrap_or_default()),
+        }
+
+        rest = after;
+    }
+
+    expanded.push_str(rest);
+
+    expanded
+}
@@ -0,0 +1,142 @@
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::{io, process::Command};
+
+/// Resource limits applied to a process `command` before it executes.
+///
+/// Each limit is applied as both the soft and the hard limit of the corresponding resource, except
+/// for `cpu`, whose hard limit is one second higher so that a `command` exceeding it is sent
+/// `SIGXCPU` before it is killed. Limits are inherited by any children the `command` spawns.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Limits {
+    pub address_space: Option<u64>,
+    pub core_size: Option<u64>,
+    pub cpu: Option<u64>,
+    pub open_files: Option<u64>,
+    pub processes: Option<u64>,
+}
+
+impl Limits {
+    /// Constructs a new, empty `Limits`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds `Limits` with the maximum size of virtual memory, in bytes.
+    pub fn address_space(mut self, a: Option<u64>) -> Self {
+        self.address_space = a;
+
+        self
+    }
+
+    /// Builds `Limits` with the maximum size of core dumps, in bytes.
+    pub fn core_size(mut self, c: Option<u64>) -> Self {
+        self.core_size = c;
+
+        self
+    }
+
+    /// Builds `Limits` with the maximum CPU time, in seconds.
+    pub fn cpu(mut self, c: Option<u64>) -> Self {
+        self.cpu = c;
+
+        self
+    }
+
+    /// Builds `Limits` with the maximum number of open file descriptors.
+    pub fn open_files(mut self, o: Option<u64>) -> Self {
+        self.open_files = o;
+
+        self
+    }
+
+    /// Builds `Limits` with the maximum number of processes for the user running the `command`.
+    pub fn processes(mut self, p: Option<u64>) -> Self {
+        self.processes = p;
+
+        self
+    }
+
+    /// Returns whether no limits are set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Applies the limits to the command in a pre-exec hook.
+    #[cfg(unix)]
+    pub fn apply(&self, command: &mut Command) -> io::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let limits = *self;
+
+        unsafe {
+            command.pre_exec(move || {
+                set_limit(
+                    |limit| libc::setrlimit(libc::RLIMIT_AS, limit),
+                    limits.address_space,
+                    0,
+                )?;
+                set_limit(
+                    |limit| libc::setrlimit(libc::RLIMIT_CORE, limit),
+                    limits.core_size,
+                    0,
+                )?;
+                set_limit(
+                    |limit| libc::setrlimit(libc::RLIMIT_CPU, limit),
+                    limits.cpu,
+                    1,
+                )?;
+                set_limit(
+                    |limit| libc::setrlimit(libc::RLIMIT_NOFILE, limit),
+                    limits.open_files,
+                    0,
+                )?;
+                set_limit(
+                    |limit| libc::setrlimit(libc::RLIMIT_NPROC, limit),
+                    limits.processes,
+                    0,
+                )?;
+
+                Ok(())
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply(&self, _command: &mut Command) -> io::Result<()> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Resource limits are not supported on this platform",
+            )),
+        }
+    }
+}
+
+/// Sets the soft limit of a resource to the specified value, if any, and its hard limit to the
+/// value plus `headroom`, using the provided call to `setrlimit`.
+#[cfg(unix)]
+fn set_limit(
+    setrlimit: impl Fn(*const libc::rlimit) -> libc::c_int,
+    value: Option<u64>,
+    headroom: u64,
+) -> io::Result<()> {
+    let value = match value {
+        Some(value) => value as libc::rlim_t,
+        None => return Ok(()),
+    };
+    let limit = libc::rlimit {
+        rlim_cur: value,
+        rlim_max: value.saturating_add(headroom as libc::rlim_t),
+    };
+
+    match setrlimit(&limit) {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
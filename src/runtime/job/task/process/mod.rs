@@ -1,48 +1,183 @@
+pub mod backoff;
+pub mod capture;
+pub mod encoding;
+pub mod exit_condition;
+mod expand;
+pub mod limits;
+mod pty;
+pub mod readiness;
+pub mod restart;
+pub mod stdin;
 mod stream;
 
 use crate::runtime::{
     ctx::Ctx,
     job::task::{
-        action::ProcessActions,
+        action::Action,
+        completion::Completion,
         log_monitor::message::{LogMonitorCmd, LogMonitorMessage},
     },
     local_bin::BinCommand,
+    scope::Parent,
+    signal,
 };
 use anyhow::{bail, Context, Result};
+use backoff::Backoff;
+use capture::{Capture, Capturer};
 use crossbeam_channel::Sender;
+use encoding::Encoding;
+use exit_condition::ExitCondition;
+use expand::expand_env;
+use limits::Limits;
 use log::{debug, info};
-use std::process::{Command, Stdio};
-use stream::PipeStreamReader;
+use pty::Pty;
+use readiness::{ProbeTarget, Prober, Readiness};
+use restart::Restart;
+#[cfg(unix)]
+use std::mem;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::{
+    collections::HashMap,
+    env::var,
+    io,
+    process::{Child, Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+use stdin::Stdin;
+use stream::{ChildOutput, StreamEnd};
+
+/// Interval at which a child whose output has closed is polled for its exit.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Represents and contains a given runtime job task process.
 ///
 /// This object contains all of the data necessary to run a given process. This data includes the
-/// process name, the `command` which should be executed using the current `BinCommand`, the
-/// directory in which to execute the `command`, any log monitors which should monitor the command
-/// output, as well as any actions which should be performed when the command fails or succeeds.
+/// process name, the `command` which should be executed using either the process `BinCommand` or
+/// the current one, or the `argv` which should be executed directly, the directory in which to
+/// execute the `command`, the processes on which the process depends, any environment variables to
+/// set for the `command`, the source from which the `command` reads its stdin, whether the
+/// `command` should be attached to a pseudo-terminal, the resource limits, user, group, and umask
+/// with which to execute the `command`, how to read the command output, any log monitors which
+/// should monitor the command output, the variable into which to capture the command output, how to
+/// tell when the command is ready, the maximum duration for which the command may run, how the
+/// command should be retried and restarted, how the command should be stopped, as well as any
+/// actions which should be performed, in order or concurrently, when the command fails, succeeds,
+/// times out, exits with a specific status, or crash loops.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Process {
+    pub argv: Vec<String>,
+    pub backoff: Backoff,
+    pub bin_command: Option<BinCommand>,
+    pub capture: Option<Capture>,
     pub command: String,
+    pub concurrent_actions: bool,
     pub cwd: String,
+    pub depends_on: Vec<String>,
+    pub encoding: Encoding,
+    pub env: HashMap<String, String>,
+    pub expand_env: bool,
+    pub group: Option<u32>,
+    pub limits: Limits,
     pub log_monitors: Vec<String>,
+    pub max_line_length: usize,
     pub name: String,
-    pub onfail: Option<String>,
-    pub onsucceed: Option<String>,
+    pub onexit: Vec<(ExitCondition, Vec<String>)>,
+    pub onfail: Vec<String>,
+    pub oncrashloop: Vec<String>,
+    pub onsucceed: Vec<String>,
+    pub ontimeout: Vec<String>,
+    pub ready_when: Option<Readiness>,
+    pub restart: Restart,
+    pub retries: u32,
+    pub retry_delay: Duration,
+    pub stdin: Stdin,
+    pub stop_signal: Option<i32>,
+    pub stop_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub tty: bool,
+    pub umask: Option<u32>,
+    pub user: Option<u32>,
 }
 
 impl Process {
     /// Constructs a new, empty `Process`.
     pub fn new(name: String) -> Self {
         Self {
+            argv: Vec::new(),
+            backoff: Backoff::Fixed,
+            bin_command: None,
+            capture: None,
             command: String::new(),
+            concurrent_actions: false,
             cwd: ".".to_owned(),
+            depends_on: Vec::new(),
+            encoding: Encoding::Utf8,
+            env: HashMap::new(),
+            expand_env: false,
+            group: None,
+            limits: Limits::new(),
             log_monitors: Vec::new(),
+            max_line_length: 1024 * 1024,
             name,
-            onfail: None,
-            onsucceed: None,
+            onexit: Vec::new(),
+            onfail: Vec::new(),
+            oncrashloop: Vec::new(),
+            onsucceed: Vec::new(),
+            ontimeout: Vec::new(),
+            ready_when: None,
+            restart: Restart::default(),
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            stdin: Stdin::Null,
+            stop_signal: None,
+            stop_timeout: None,
+            timeout: None,
+            tty: false,
+            umask: None,
+            user: None,
         }
     }
 
+    /// Builds `Process` with the program and arguments to execute directly, without a shell.
+    ///
+    /// If not empty, this is executed in place of `command`, and `BinCommand` is not used.
+    pub fn argv(mut self, a: Vec<String>) -> Self {
+        self.argv = a;
+
+        self
+    }
+
+    /// Builds `Process` with the specified backoff strategy.
+    ///
+    /// This strategy determines how `retry_delay` grows between retries and how the restart delay
+    /// grows between restarts.
+    pub fn backoff(mut self, b: Backoff) -> Self {
+        self.backoff = b;
+
+        self
+    }
+
+    /// Builds `Process` with the `BinCommand` used to execute `command`.
+    ///
+    /// If not specified, the runtime `BinCommand` is used.
+    pub fn bin_command(mut self, c: Option<BinCommand>) -> Self {
+        self.bin_command = c;
+
+        self
+    }
+
+    /// Builds `Process` with the capture of the stdout of `command` into a runtime variable.
+    ///
+    /// The variable is set once `command` succeeds and is exposed to subsequently executed
+    /// processes and log monitor tests as `ARPX_VAR_<NAME>`.
+    pub fn capture(mut self, c: Option<Capture>) -> Self {
+        self.capture = c;
+
+        self
+    }
+
     /// Builds `Process` with the specified command.
     pub fn command(mut self, c: String) -> Self {
         self.command = c;
@@ -50,6 +185,14 @@ impl Process {
         self
     }
 
+    /// Builds `Process` with whether to perform the actions of a hook concurrently, rather than in
+    /// order.
+    pub fn concurrent_actions(mut self, c: bool) -> Self {
+        self.concurrent_actions = c;
+
+        self
+    }
+
     /// Builds `Process` with the specified current working directory.
     ///
     /// This directory is where `command` will be executed using the runtime `BinCommand`.
@@ -59,6 +202,58 @@ impl Process {
         self
     }
 
+    /// Builds `Process` with the names of the processes on which it depends.
+    ///
+    /// Before the `command` is executed, each dependency which has been run by the same job must
+    /// have succeeded or, if it has a readiness probe, must be ready. Otherwise, the process is
+    /// skipped.
+    pub fn depends_on(mut self, d: Vec<String>) -> Self {
+        self.depends_on = d;
+
+        self
+    }
+
+    /// Builds `Process` with the encoding in which `command` writes its output.
+    pub fn encoding(mut self, e: Encoding) -> Self {
+        self.encoding = e;
+
+        self
+    }
+
+    /// Builds `Process` with the specified environment variables.
+    ///
+    /// These variables are set on `command` in addition to those captured by the runtime and those
+    /// inherited from the runtime.
+    pub fn env(mut self, e: HashMap<String, String>) -> Self {
+        self.env = e;
+
+        self
+    }
+
+    /// Builds `Process` with whether to expand environment variables in `argv`.
+    ///
+    /// Variables are expanded from those set on the `command`, including those inherited from the
+    /// runtime.
+    pub fn expand_env(mut self, e: bool) -> Self {
+        self.expand_env = e;
+
+        self
+    }
+
+    /// Builds `Process` with the id of the group as which `command` is executed.
+    pub fn group(mut self, g: Option<u32>) -> Self {
+        self.group = g;
+
+        self
+    }
+
+    /// Builds `Process` with the resource limits applied to `command`.
+    pub fn limits(mut self, l: Limits) -> Self {
+        self.limits = l;
+
+        self
+    }
+
     /// Builds `Process` with the specified log monitors.
     pub fn log_monitors(mut self, m: Vec<String>) -> Self {
         self.log_monitors = m;
@@ -66,108 +261,817 @@ impl Process {
         self
     }
 
-    /// Builds `Process` with the name of the action to execute if the `command` fails.
-    pub fn onfail(mut self, f: Option<String>) -> Self {
+    /// Builds `Process` with the maximum length of a line of output, in bytes.
+    ///
+    /// Longer lines written by `command` are truncated. Defaults to 1 MiB.
+    pub fn max_line_length(mut self, m: usize) -> Self {
+        self.max_line_length = m;
+
+        self
+    }
+
+    /// Builds `Process` with the names of actions to execute when the `command` exits with a
+    /// matching status.
+    ///
+    /// A matching `onexit` action takes precedence over `onsucceed` and `onfail`. If several
+    /// conditions match, the most specific one is used.
+    pub fn onexit(mut self, e: Vec<(ExitCondition, Vec<String>)>) -> Self {
+        self.onexit = e;
+
+        self
+    }
+
+    /// Builds `Process` with the names of the actions to execute if the `command` fails.
+    pub fn onfail(mut self, f: Vec<String>) -> Self {
         self.onfail = f;
 
         self
     }
 
-    /// Builds `Process` with the name of the action to execute if the `command` succeeds.
-    pub fn onsucceed(mut self, s: Option<String>) -> Self {
+    /// Builds `Process` with the names of the actions to execute if the process is crash looping.
+    ///
+    /// If no `oncrashloop` actions are specified, the `onfail` actions are executed instead.
+    pub fn oncrashloop(mut self, c: Vec<String>) -> Self {
+        self.oncrashloop = c;
+
+        self
+    }
+
+    /// Builds `Process` with the names of the actions to execute if the `command` succeeds.
+    pub fn onsucceed(mut self, s: Vec<String>) -> Self {
         self.onsucceed = s;
 
         self
     }
 
-    /// Executes the process using the provided actions, context, and log monitor connections.
+    /// Builds `Process` with the names of the actions to execute if the `command` times out.
+    ///
+    /// If no `ontimeout` actions are specified, the `onfail` actions are executed instead.
+    pub fn ontimeout(mut self, t: Vec<String>) -> Self {
+        self.ontimeout = t;
+
+        self
+    }
+
+    /// Builds `Process` with the readiness probe of the `command`.
+    ///
+    /// A task which runs a process with a readiness probe continues once the `command` is ready,
+    /// leaving the `command` running until it exits.
+    pub fn ready_when(mut self, r: Option<Readiness>) -> Self {
+        self.ready_when = r;
+
+        self
+    }
+
+    /// Builds `Process` with the restart policy of the process.
+    ///
+    /// Once the `command` exits, including any retries, it is restarted as configured by the
+    /// policy until its restart budget is exhausted, at which point the `oncrashloop` action is
+    /// executed. Other actions are only executed once the process is no longer restarted.
+    pub fn restart(mut self, r: Restart) -> Self {
+        self.restart = r;
+
+        self
+    }
+
+    /// Builds `Process` with the number of times to retry the `command` if it fails or times out.
+    ///
+    /// The current attempt number, starting from 1, is exposed to `command` as `ARPX_ATTEMPT`.
+    pub fn retries(mut self, r: u32) -> Self {
+        self.retries = r;
+
+        self
+    }
+
+    /// Builds `Process` with the delay to wait before the first retry of the `command`.
+    pub fn retry_delay(mut self, d: Duration) -> Self {
+        self.retry_delay = d;
+
+        self
+    }
+
+    /// Builds `Process` with the source from which `command` reads its stdin.
+    pub fn stdin(mut self, s: Stdin) -> Self {
+        self.stdin = s;
+
+        self
+    }
+
+    /// Builds `Process` with the signal used to stop the `command`.
+    ///
+    /// Whenever the `command` is stopped, whether due to a timeout or a runtime shutdown, this
+    /// signal is sent to it first. If not specified, the signal received by the runtime is
+    /// forwarded, or `SIGTERM` is sent if there is none.
+    pub fn stop_signal(mut self, s: Option<i32>) -> Self {
+        self.stop_signal = s;
+
+        self
+    }
+
+    /// Builds `Process` with the duration to wait for the `command` to exit after its stop signal
+    /// is sent, after which it is killed.
+    ///
+    /// If not specified, the runtime grace period is used.
+    pub fn stop_timeout(mut self, t: Option<Duration>) -> Self {
+        self.stop_timeout = t;
+
+        self
+    }
+
+    /// Builds `Process` with the specified timeout.
+    ///
+    /// If `command` runs for longer than this duration, it is stopped and treated as timed out.
+    pub fn timeout(mut self, t: Option<Duration>) -> Self {
+        self.timeout = t;
+
+        self
+    }
+
+    /// Builds `Process` with whether `command` should be attached to a pseudo-terminal.
+    ///
+    /// Attaching `command` to a pseudo-terminal lets it detect that it is running interactively,
+    /// so that it keeps its colors and progress output. Its stdout and stderr are then read as a
+    /// single stream.
+    pub fn tty(mut self, t: bool) -> Self {
+        self.tty = t;
+
+        self
+    }
+
+    /// Builds `Process` with the umask with which `command` is executed.
+    pub fn umask(mut self, u: Option<u32>) -> Self {
+        self.umask = u;
+
+        self
+    }
+
+    /// Builds `Process` with the id of the user as which `command` is executed.
+    ///
+    /// Supplementary groups are dropped when a user is specified.
+    pub fn user(mut self, u: Option<u32>) -> Self {
+        self.user = u;
+
+        self
+    }
+
+    /// Executes the process using the provided context and log monitor connections.
+    ///
+    /// If the process has a readiness probe, `ready` is settled once the `command` is ready.
+    /// Returns whether the final attempt of the `command` succeeded, along with the actions which
+    /// should be performed as a result.
+    ///
+    /// If the `command` fails or times out, it is attempted again up to `retries` times, waiting
+    /// between attempts as configured by `retry_delay` and `backoff`. Actions are only returned
+    /// once the final attempt has exited. If the runtime shuts down, the `command` is stopped and
+    /// no further attempts are made or actions returned.
     pub fn run(
         &self,
-        actions: ProcessActions,
         ctx: &Ctx,
         log_monitor_senders: &[Sender<LogMonitorMessage>],
-    ) -> Result<()> {
+        ready: Option<Completion>,
+    ) -> Result<Outcome> {
         debug!("Initiating process \"{}\"", self.name);
 
-        let BinCommand { bin, mut args } = ctx.bin_command.clone();
-        args.push(self.command.clone());
+        let exit = self.serve(ctx, log_monitor_senders, ready.as_ref());
 
-        debug!(
-            "Building command and invoking on local binary \"{}\" with args {:?}",
-            bin, args
-        );
-        let mut child = Command::new(bin)
-            .args(args)
+        for sender in log_monitor_senders.iter() {
+            if sender
+                .send(LogMonitorMessage::new().cmd(LogMonitorCmd::Close))
+                .is_err()
+            {
+                bail!(
+                    "Error sending process close message to log monitor on process \"{}\"",
+                    self.name
+                );
+            }
+        }
+
+        let (exit, hook, names) = match exit? {
+            Some(FinalExit::Exited(exit)) if !ctx.shutdown.is_triggered() => {
+                let onexit_index =
+                    ExitCondition::best_match(self.onexit.iter().map(|(c, _)| c), &exit.status);
+
+                let (hook, names): (&str, &[String]) =
+                    if exit.termination == Some(Termination::TimedOut) {
+                        ("ontimeout", self.or_onfail(&self.ontimeout))
+                    } else if let Some(index) = onexit_index {
+                        ("onexit", &self.onexit[index].1)
+                    } else if exit.status.success() {
+                        ("onsucceed", &self.onsucceed)
+                    } else {
+                        ("onfail", &self.onfail)
+                    };
+
+                (exit, hook, names)
+            }
+            Some(FinalExit::CrashLooped(exit)) if !ctx.shutdown.is_triggered() => {
+                (exit, "oncrashloop", self.or_onfail(&self.oncrashloop))
+            }
+            _ => {
+                debug!(
+                    "Runtime is shutting down, skipping actions on process \"{}\"",
+                    self.name
+                );
+
+                return Ok(Outcome::default());
+            }
+        };
+
+        let actions = names
+            .iter()
+            .map(|name| {
+                debug!(
+                    "Queueing {} \"{}\" on process \"{}\"",
+                    hook, name, self.name
+                );
+
+                Action::new(name.clone(), self.parent(&exit))
+            })
+            .collect();
+
+        Ok(Outcome {
+            actions,
+            concurrent: self.concurrent_actions,
+            succeeded: exit.succeeded(),
+        })
+    }
+
+    /// Attempts the `command` and restarts it until it should no longer be restarted, its restart
+    /// budget is exhausted, or the runtime shuts down. Returns the final exit of the process, if
+    /// any attempt was made.
+    fn serve(
+        &self,
+        ctx: &Ctx,
+        log_monitor_senders: &[Sender<LogMonitorMessage>],
+        ready: Option<&Completion>,
+    ) -> Result<Option<FinalExit>> {
+        let mut budget = self.restart.budget();
+
+        loop {
+            let exit = match self.attempt(ctx, log_monitor_senders, ready)? {
+                Some(exit) => exit,
+                None => return Ok(None),
+            };
+
+            if ctx.shutdown.is_triggered() || !self.restart.applies(exit.succeeded()) {
+                return Ok(Some(FinalExit::Exited(exit)));
+            }
+
+            let restarts = match budget.spend() {
+                Some(restarts) => restarts,
+                None => {
+                    let max_restarts = self.restart.max_restarts.unwrap_or_default();
+
+                    match self.restart.window {
+                        Some(window) => info!(
+                            "\"{}\" crash looping, restarted {} times within {:?}",
+                            self.name, max_restarts, window
+                        ),
+                        None => info!(
+                            "\"{}\" crash looping, restarted {} times",
+                            self.name, max_restarts
+                        ),
+                    }
+
+                    return Ok(Some(FinalExit::CrashLooped(exit)));
+                }
+            };
+            let delay = self.backoff.delay(self.restart.delay, restarts);
+
+            info!(
+                "\"{}\" restarting in {:?} (restart {})",
+                self.name,
+                delay,
+                budget.total()
+            );
+
+            if ctx.shutdown.wait_timeout(delay) {
+                return Ok(Some(FinalExit::Exited(exit)));
+            }
+        }
+    }
+
+    /// Attempts the `command` until it succeeds, the retries are exhausted, or the runtime shuts
+    /// down. Returns the exit of the final attempt, if any attempt was made.
+    fn attempt(
+        &self,
+        ctx: &Ctx,
+        log_monitor_senders: &[Sender<LogMonitorMessage>],
+        ready: Option<&Completion>,
+    ) -> Result<Option<ProcessExit>> {
+        let mut attempt = 1;
+
+        loop {
+            if ctx.shutdown.is_triggered() {
+                return Ok(None);
+            }
+
+            let exit = self.execute(attempt, ctx, log_monitor_senders, ready)?;
+
+            if exit.succeeded()
+                || exit.termination == Some(Termination::Stopped)
+                || attempt > self.retries
+            {
+                return Ok(Some(exit));
+            }
+
+            let delay = self.backoff.delay(self.retry_delay, attempt);
+            attempt += 1;
+
+            info!(
+                "\"{}\" retrying in {:?} (attempt {} of {})",
+                self.name,
+                delay,
+                attempt,
+                self.retries + 1
+            );
+
+            if ctx.shutdown.wait_timeout(delay) {
+                return Ok(Some(exit));
+            }
+        }
+    }
+
+    /// Spawns a single attempt of the `command`, streams its output, and waits for it to exit.
+    fn execute(
+        &self,
+        attempt: u32,
+        ctx: &Ctx,
+        log_monitor_senders: &[Sender<LogMonitorMessage>],
+        ready: Option<&Completion>,
+    ) -> Result<ProcessExit> {
+        let attempt = attempt.to_string();
+        let env = self.resolved_env(ctx);
+        let mut command = match self.argv.split_first() {
+            Some((program, args)) => {
+                let expand = |arg: &String| match self.expand_env {
+                    true => expand_env(arg, |name| match name {
+                        "ARPX_ATTEMPT" => Some(attempt.clone()),
+                        name => env.get(name).cloned().or_else(|| var(name).ok()),
+                    }),
+                    false => arg.clone(),
+                };
+                let (program, args) =
+                    (expand(program), args.iter().map(expand).collect::<Vec<_>>());
+
+                debug!(
+                    "Building command and invoking \"{}\" directly with args {:?}",
+                    program, args
+                );
+                let mut command = Command::new(program);
+                command.args(args);
+
+                command
+            }
+            None => {
+                let BinCommand { bin, mut args } = self.resolved_bin_command(ctx);
+                args.push(self.command.clone());
+
+                debug!(
+                    "Building command and invoking on local binary \"{}\" with args {:?}",
+                    bin, args
+                );
+                let mut command = Command::new(bin);
+                command.args(args);
+
+                command
+            }
+        };
+        command
             .current_dir(&self.cwd[..])
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
+            .envs(&env)
+            .env("ARPX_ATTEMPT", &attempt);
+
+        #[cfg(unix)]
+        self.apply_credentials(&mut command);
+
+        self.limits.apply(&mut command).context(format!(
+            "Error applying limits on process \"{}\"",
+            self.name
+        ))?;
+
+        let pty = match self.tty {
+            true => Some(
+                Pty::attach(&mut command)
+                    .context(format!("Error opening pty on process \"{}\"", self.name))?,
+            ),
+            false => {
+                self.stdin
+                    .attach(&mut command)
+                    .context(format!("Error opening stdin on process \"{}\"", self.name))?;
+                command.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+                #[cfg(unix)]
+                command.process_group(0);
+
+                None
+            }
+        };
+
+        let mut capturer = self
+            .capture
+            .as_ref()
+            .map(Capture::capturer)
+            .transpose()
             .context(format!(
-                "Error spawning process command on process \"{}\"",
+                "Error starting capture on process \"{}\"",
                 self.name
             ))?;
 
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(error) => {
+                info!(
+                    "\"{}\" failed, error spawning command: {}",
+                    self.name, error
+                );
+
+                return Ok(ProcessExit {
+                    status: exit_condition::spawn_failure(),
+                    termination: None,
+                });
+            }
+        };
+
+        // Release the parent's handles to the pty so that reads fail once the child exits.
+        drop(command);
+
+        self.stdin.feed(&mut child);
+
         info!("\"{}\" ({}) spawned", self.name, child.id());
 
         let pid = child.id();
 
+        ctx.registry.insert(pid, self.name.clone());
+
         debug!("Begin streaming output from \"{}\" ({})", self.name, pid);
-        PipeStreamReader::stream_child_output(&mut child, log_monitor_senders)
-            .context("Output stream error")?;
+        let termination = self
+            .supervise(
+                &mut child,
+                pty,
+                ctx,
+                log_monitor_senders,
+                ready,
+                capturer.as_mut(),
+            )
+            .context("Output stream error");
+
+        // Kill any children left in the process group of a terminated child before it is reaped,
+        // while its pid still reserves the group id.
+        if !matches!(termination, Ok(None)) {
+            signal_child(&mut child, signal::SIGKILL).ok();
+        }
 
         debug!("Waiting on close... \"{}\" ({})", self.name, pid);
         let status = child.wait().context(format!(
             "Error waiting for process command child on process \"{}\"",
             self.name
-        ))?;
+        ));
+
+        ctx.registry.remove(pid);
+        self.stdin.release();
+
+        let termination = termination?;
+        let status = status?;
 
         debug!(
             "Process \"{}\" ({}) closed with exit status: {:?}",
             self.name, pid, status
         );
 
-        for sender in log_monitor_senders.iter() {
-            if sender
-                .send(LogMonitorMessage::new().cmd(LogMonitorCmd::Close))
-                .is_err()
-            {
-                bail!(
-                    "Error sending process close message to log monitor on process \"{}\"",
-                    self.name
-                );
+        if let (None, true, Some(capture), Some(capturer)) =
+            (&termination, status.success(), &self.capture, capturer)
+        {
+            if let Some(value) = capturer.finish() {
+                debug!("Process \"{}\" captured {}", self.name, capture.var);
+
+                ctx.vars.set(capture.var.clone(), value);
             }
         }
 
-        if status.success() {
-            info!("\"{}\" ({}) succeeded", self.name, pid);
+        match termination {
+            Some(Termination::Stopped) => info!("\"{}\" ({}) stopped", self.name, pid),
+            Some(Termination::TimedOut) => info!("\"{}\" ({}) timed out", self.name, pid),
+            None if status.success() => info!("\"{}\" ({}) succeeded", self.name, pid),
+            None => match exit_condition::terminating_signal(&status) {
+                Some(terminating_signal) => info!(
+                    "\"{}\" ({}) failed, killed by {}",
+                    self.name,
+                    pid,
+                    self.describe_signal(terminating_signal)
+                ),
+                None => info!("\"{}\" ({}) failed", self.name, pid),
+            },
+        }
 
-            if let Some(onsucceed) = actions.onsucceed {
-                let onsucceed_name = match &self.onsucceed {
-                    Some(n) => n.clone(),
-                    None => "".to_string(),
-                };
-                debug!(
-                    "Running onsucceed \"{}\" from prepared actions",
-                    onsucceed_name
-                );
+        Ok(ProcessExit {
+            status,
+            termination,
+        })
+    }
+
+    /// Streams output from the child until it exits, terminating it if it times out, if it is not
+    /// ready in time, or if the runtime shuts down. Stdout is collected in the capturer, if
+    /// provided. Returns the reason for which the child was terminated, if any.
+    fn supervise(
+        &self,
+        child: &mut Child,
+        pty: Option<Pty>,
+        ctx: &Ctx,
+        log_monitor_senders: &[Sender<LogMonitorMessage>],
+        ready: Option<&Completion>,
+        mut capturer: Option<&mut Capturer>,
+    ) -> Result<Option<Termination>> {
+        let mut output = match pty {
+            Some(pty) => ChildOutput::pty(pty, self.encoding, self.max_line_length)?,
+            None => ChildOutput::new(child, self.encoding, self.max_line_length)?,
+        }
+        .strip_ansi(ctx.strip_ansi);
+        let prober = match &self.ready_when {
+            Some(readiness) => Some(
+                readiness
+                    .watch(
+                        &self.name,
+                        child.id(),
+                        ProbeTarget {
+                            bin_command: self.resolved_bin_command(ctx),
+                            cwd: self.cwd.clone(),
+                            env: self.resolved_env(ctx),
+                        },
+                        ready.cloned(),
+                    )
+                    .context("Error starting readiness probe")?,
+            ),
+            None => None,
+        };
+        let mut senders = log_monitor_senders.to_vec();
+        senders.extend(prober.as_ref().and_then(Prober::log_sender).cloned());
+        let mut deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut termination = None;
 
-                onsucceed();
+        loop {
+            let interrupt = match termination {
+                None => Some(ctx.shutdown.receiver()),
+                Some(_) => None,
+            };
+            let ready_deadline = match termination {
+                None => prober.as_ref().and_then(Prober::deadline),
+                Some(_) => None,
+            };
+            let stream_deadline = match (deadline, ready_deadline) {
+                (Some(deadline), Some(ready_deadline)) => Some(deadline.min(ready_deadline)),
+                (deadline, ready_deadline) => deadline.or(ready_deadline),
+            };
+
+            match output.stream(
+                &senders,
+                stream_deadline,
+                interrupt,
+                capturer.as_deref_mut(),
+            )? {
+                StreamEnd::Closed => {
+                    return self.wait_for_exit(child, ctx, deadline, termination, prober.as_ref())
+                }
+                StreamEnd::Deadline if termination.is_some() => {
+                    debug!(
+                        "Process \"{}\" ({}) did not stop in time, killing",
+                        self.name,
+                        child.id()
+                    );
+                    signal_child(child, signal::SIGKILL)?;
+
+                    return Ok(termination);
+                }
+                StreamEnd::Deadline => {
+                    if !self.expired(child, deadline, prober.as_ref()) {
+                        continue;
+                    }
+
+                    deadline = Some(self.stop(child, ctx)?);
+                    termination = Some(Termination::TimedOut);
+                }
+                StreamEnd::Interrupted => {
+                    deadline = Some(self.stop(child, ctx)?);
+                    termination = Some(Termination::Stopped);
+                }
+            }
+        }
+    }
+
+    /// Waits for a child whose output has closed to exit, enforcing the same deadlines as while its
+    /// output was streamed and stopping it if the runtime shuts down. Returns the reason for which
+    /// the child was terminated, if any.
+    fn wait_for_exit(
+        &self,
+        child: &mut Child,
+        ctx: &Ctx,
+        mut deadline: Option<Instant>,
+        mut termination: Option<Termination>,
+        prober: Option<&Prober>,
+    ) -> Result<Option<Termination>> {
+        loop {
+            if has_exited(child)? {
+                return Ok(termination);
             }
-        } else {
-            info!("\"{}\" ({}) failed", self.name, pid);
 
-            if let Some(onfail) = actions.onfail {
-                let onfail_name = match &self.onfail {
-                    Some(n) => n.clone(),
-                    None => "".to_owned(),
-                };
-                debug!("Running onfail \"{}\" from prepared actions", onfail_name);
+            match termination {
+                Some(_) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                    debug!(
+                        "Process \"{}\" ({}) did not stop in time, killing",
+                        self.name,
+                        child.id()
+                    );
+                    signal_child(child, signal::SIGKILL)?;
 
-                onfail();
+                    return Ok(termination);
+                }
+                None if self.expired(child, deadline, prober) => {
+                    deadline = Some(self.stop(child, ctx)?);
+                    termination = Some(Termination::TimedOut);
+                }
+                None if ctx.shutdown.wait_timeout(EXIT_POLL_INTERVAL) => {
+                    deadline = Some(self.stop(child, ctx)?);
+                    termination = Some(Termination::Stopped);
+                }
+                None => {}
+                Some(_) => thread::sleep(EXIT_POLL_INTERVAL),
             }
         }
+    }
+
+    /// Returns whether the child has timed out, or has not become ready in time.
+    fn expired(&self, child: &Child, deadline: Option<Instant>, prober: Option<&Prober>) -> bool {
+        let now = Instant::now();
+        let timed_out = deadline.is_some_and(|deadline| now >= deadline);
+        let not_ready = prober
+            .and_then(Prober::deadline)
+            .is_some_and(|ready_deadline| now >= ready_deadline);
+
+        if not_ready {
+            info!("\"{}\" ({}) not ready in time", self.name, child.id());
+        }
+
+        timed_out || not_ready
+    }
+
+    /// Returns the `BinCommand` of the process, or that of the runtime if none is specified.
+    fn resolved_bin_command(&self, ctx: &Ctx) -> BinCommand {
+        self.bin_command
+            .clone()
+            .unwrap_or_else(|| ctx.bin_command.clone())
+    }
+
+    /// Returns the variables captured by the runtime along with the environment variables of the
+    /// process, which take precedence, and the scope from which the process is executed.
+    fn resolved_env(&self, ctx: &Ctx) -> HashMap<String, String> {
+        let mut env = ctx.vars.env();
+        env.extend(self.env.clone());
+        env.extend(ctx.scope.env(&self.name));
+
+        env
+    }
+
+    /// Returns the specified actions, or the `onfail` actions if none are specified.
+    fn or_onfail<'a>(&'a self, actions: &'a [String]) -> &'a [String] {
+        match actions.is_empty() {
+            true => &self.onfail,
+            false => actions,
+        }
+    }
+
+    /// Returns the process as the parent of an action performed once it exited as specified.
+    fn parent(&self, exit: &ProcessExit) -> Parent {
+        Parent {
+            exit_code: exit.status.code(),
+            process: self.name.clone(),
+            trigger_buffer: None,
+        }
+    }
+
+    /// Sets the user, group, and umask with which the `command` is executed.
+    #[cfg(unix)]
+    fn apply_credentials(&self, command: &mut Command) {
+        if let Some(gid) = self.group {
+            command.gid(gid);
+        }
+
+        if let Some(uid) = self.user {
+            command.uid(uid);
+        }
+
+        if let Some(umask) = self.umask {
+            unsafe {
+                command.pre_exec(move || {
+                    libc::umask(umask as libc::mode_t);
+
+                    Ok(())
+                });
+            }
+        }
+    }
+
+    /// Describes a signal which terminated the `command`, noting the limit it indicates was
+    /// exceeded, if any.
+    fn describe_signal(&self, terminating_signal: i32) -> String {
+        let name = match signal::name(terminating_signal) {
+            Some(name) => name.to_owned(),
+            None => format!("signal {}", terminating_signal),
+        };
+
+        match terminating_signal {
+            signal::SIGXCPU if self.limits.cpu.is_some() => {
+                format!("{} (cpu limit exceeded)", name)
+            }
+            _ => name,
+        }
+    }
+
+    /// Sends the stop signal to the child. Returns the deadline after which the child should be
+    /// killed.
+    fn stop(&self, child: &mut Child, ctx: &Ctx) -> Result<Instant> {
+        let stop_signal = self
+            .stop_signal
+            .or_else(|| ctx.shutdown.signal())
+            .unwrap_or(signal::SIGTERM);
+
+        debug!(
+            "Stopping process \"{}\" ({}) with {}",
+            self.name,
+            child.id(),
+            signal::name(stop_signal).unwrap_or("signal")
+        );
+        signal_child(child, stop_signal)?;
+
+        Ok(Instant::now() + self.stop_timeout.unwrap_or(ctx.grace_period))
+    }
+}
+
+/// Sends the specified signal to the child along with any children it has spawned.
+#[cfg(unix)]
+fn signal_child(child: &mut Child, signal: i32) -> io::Result<()> {
+    match signal::send_to_group(child.id(), signal) {
+        Err(error) if error.raw_os_error() == Some(libc::ESRCH) => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_child(child: &mut Child, _signal: i32) -> io::Result<()> {
+    child.kill()
+}
+
+/// Returns whether the child has exited, without reaping it.
+#[cfg(unix)]
+fn has_exited(child: &mut Child) -> io::Result<bool> {
+    let mut info: libc::siginfo_t = unsafe { mem::zeroed() };
+
+    match unsafe {
+        libc::waitid(
+            libc::P_PID,
+            child.id(),
+            &mut info,
+            libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+        )
+    } {
+        0 => Ok(unsafe { info.si_pid() } != 0),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+fn has_exited(child: &mut Child) -> io::Result<bool> {
+    Ok(child.try_wait()?.is_some())
+}
+
+/// Reason for which arpx terminated a process `command`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Termination {
+    Stopped,
+    TimedOut,
+}
+
+/// Final exit of a process, once it is no longer restarted.
+enum FinalExit {
+    CrashLooped(ProcessExit),
+    Exited(ProcessExit),
+}
+
+/// Outcome of a process, once it is no longer attempted or restarted.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    pub actions: Vec<Action>,
+    pub concurrent: bool,
+    pub succeeded: bool,
+}
+
+/// The result of a single attempt of a process `command`.
+struct ProcessExit {
+    status: ExitStatus,
+    termination: Option<Termination>,
+}
 
-        Ok(())
+impl ProcessExit {
+    fn succeeded(&self) -> bool {
+        self.termination.is_none() && self.status.success()
     }
 }
@@ -0,0 +1,195 @@
+#[cfg(unix)]
+use signal_hook::{
+    consts::SIGWINCH,
+    iterator::{Handle, Signals},
+};
+#[cfg(unix)]
+use std::{
+    fs::File,
+    io::Read,
+    mem,
+    os::unix::{
+        io::{AsRawFd, FromRawFd, RawFd},
+        process::CommandExt,
+    },
+    process::Stdio,
+    ptr,
+    thread::spawn,
+};
+use std::{io, process::Command};
+
+#[cfg(unix)]
+const DEFAULT_ROWS: u16 = 24;
+#[cfg(unix)]
+const DEFAULT_COLS: u16 = 80;
+
+/// Pseudo-terminal on which a process `command` is spawned.
+///
+/// The terminal is sized to match the terminal to which arpx is attached, or 80x24 if there is
+/// none, and is resized whenever arpx receives `SIGWINCH`. Everything the child writes to its
+/// stdout and stderr is read from the master side of the terminal.
+#[cfg(unix)]
+pub struct Pty {
+    master: File,
+    resize_handle: Handle,
+}
+
+#[cfg(unix)]
+impl Pty {
+    /// Opens a new pseudo-terminal and attaches the stdio of the command to it.
+    ///
+    /// The command is made the leader of a new session with the terminal as its controlling
+    /// terminal, so it also leads its own process group.
+    pub fn attach(command: &mut Command) -> io::Result<Self> {
+        let mut master: libc::c_int = 0;
+        let mut slave: libc::c_int = 0;
+        let mut size = window_size();
+
+        if unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::addr_of_mut!(size),
+            )
+        } != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        let master = unsafe { File::from_raw_fd(master) };
+        let slave = unsafe { File::from_raw_fd(slave) };
+
+        disable_output_newline_translation(slave.as_raw_fd())?;
+
+        command
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                Ok(())
+            });
+        }
+
+        let resize_handle = Self::propagate_resize(master.try_clone()?)?;
+
+        Ok(Self {
+            master,
+            resize_handle,
+        })
+    }
+
+    /// Returns a reader over the output written to the terminal.
+    pub fn reader(&self) -> io::Result<PtyReader> {
+        Ok(PtyReader {
+            master: self.master.try_clone()?,
+        })
+    }
+
+    fn propagate_resize(master: File) -> io::Result<Handle> {
+        let mut signals = Signals::new([SIGWINCH])?;
+        let handle = signals.handle();
+
+        spawn(move || {
+            for _ in signals.forever() {
+                let size = window_size();
+
+                unsafe {
+                    libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ, &size);
+                }
+            }
+        });
+
+        Ok(handle)
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Pty {
+    fn drop(&mut self) {
+        self.resize_handle.close();
+    }
+}
+
+/// Reader over the master side of a pseudo-terminal.
+///
+/// Once every process holding the slave side has exited, reads from the master fail with `EIO`
+/// rather than returning end of file. This reader reports that condition as end of file.
+#[cfg(unix)]
+pub struct PtyReader {
+    master: File,
+}
+
+#[cfg(unix)]
+impl Read for PtyReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.master.read(buf) {
+            Err(error) if error.raw_os_error() == Some(libc::EIO) => Ok(0),
+            result => result,
+        }
+    }
+}
+
+/// Returns the size of the terminal to which arpx is attached, or the default size if there is
+/// none.
+#[cfg(unix)]
+fn window_size() -> libc::winsize {
+    let mut size: libc::winsize = unsafe { mem::zeroed() };
+
+    for fd in [libc::STDOUT_FILENO, libc::STDIN_FILENO, libc::STDERR_FILENO] {
+        if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } == 0 && size.ws_row > 0 {
+            return size;
+        }
+    }
+
+    size.ws_row = DEFAULT_ROWS;
+    size.ws_col = DEFAULT_COLS;
+
+    size
+}
+
+/// Stops the terminal from translating `\n` to `\r\n` so that output lines are read back as they
+/// were written.
+#[cfg(unix)]
+fn disable_output_newline_translation(fd: RawFd) -> io::Result<()> {
+    let mut termios: libc::termios = unsafe { mem::zeroed() };
+
+    if unsafe { libc::tcgetattr(fd, &mut termios) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    termios.c_oflag &= !libc::ONLCR;
+
+    if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &termios) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub struct Pty;
+
+#[cfg(not(unix))]
+pub type PtyReader = io::Empty;
+
+#[cfg(not(unix))]
+impl Pty {
+    pub fn attach(_command: &mut Command) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Pseudo-terminals are not supported on this platform",
+        ))
+    }
+
+    pub fn reader(&self) -> io::Result<PtyReader> {
+        Ok(io::empty())
+    }
+}
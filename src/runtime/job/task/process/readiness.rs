@@ -0,0 +1,244 @@
+use crate::runtime::{
+    job::task::{
+        completion::Completion,
+        log_monitor::message::{LogMonitorCmd, LogMonitorMessage},
+    },
+    local_bin::BinCommand,
+};
+use anyhow::{Error, Result};
+use crossbeam_channel::{unbounded, Receiver, RecvTimeoutError, Sender};
+use log::{debug, info};
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    net::{TcpStream, ToSocketAddrs},
+    path::Path,
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Check which determines whether a running process is ready.
+///
+/// `Command` is ready once the given shell command exits successfully, `File` once the file at
+/// the given path exists, `Log` once the process logs a line matching the given regular
+/// expression, and `Port` once a TCP connection can be made to the given host and port.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Probe {
+    Command(String),
+    File(String),
+    Log(String),
+    Port(String, u16),
+}
+
+/// Readiness probe of a long-running process.
+///
+/// The probe is checked every `interval` once the process spawns. If the process is not ready
+/// within `timeout`, it is stopped and treated as timed out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Readiness {
+    pub interval: Duration,
+    pub probe: Probe,
+    pub timeout: Option<Duration>,
+}
+
+impl Readiness {
+    /// Constructs a new `Readiness` from the specified probe.
+    pub fn new(probe: Probe) -> Self {
+        Self {
+            interval: Duration::from_millis(500),
+            probe,
+            timeout: None,
+        }
+    }
+
+    /// Builds `Readiness` with the specified interval between checks.
+    pub fn interval(mut self, i: Duration) -> Self {
+        self.interval = i;
+
+        self
+    }
+
+    /// Builds `Readiness` with the specified timeout.
+    pub fn timeout(mut self, t: Option<Duration>) -> Self {
+        self.timeout = t;
+
+        self
+    }
+
+    /// Begins probing a spawned attempt of the specified process on a separate thread.
+    ///
+    /// Once the probe passes, `ready` is completed, if provided. Probing stops when the
+    /// returned `Prober` is dropped.
+    pub fn watch(
+        &self,
+        name: &str,
+        pid: u32,
+        target: ProbeTarget,
+        ready: Option<Completion>,
+    ) -> Result<Prober> {
+        let (stop_sender, stop_receiver) = unbounded::<()>();
+        let (log_sender, log_receiver) = match self.probe {
+            Probe::Log(_) => {
+                let (sender, receiver) = unbounded();
+
+                (Some(sender), Some(receiver))
+            }
+            _ => (None, None),
+        };
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let is_ready = Arc::new(AtomicBool::new(false));
+
+        let check = Check {
+            probe: self.probe.clone(),
+            pattern: match &self.probe {
+                Probe::Log(pattern) => Some(Regex::new(pattern)?),
+                _ => None,
+            },
+            log_receiver,
+            target,
+        };
+        let interval = self.interval;
+        let cloned_name = name.to_owned();
+        let cloned_is_ready = is_ready.clone();
+
+        thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || {
+                loop {
+                    if check.passes() {
+                        info!("\"{}\" ({}) ready", cloned_name, pid);
+                        cloned_is_ready.store(true, Ordering::SeqCst);
+
+                        if let Some(ready) = ready {
+                            ready.complete(true);
+                        }
+
+                        break;
+                    }
+
+                    let wait = match deadline {
+                        Some(deadline) => {
+                            interval.min(deadline.saturating_duration_since(Instant::now()))
+                        }
+                        None => interval,
+                    };
+
+                    if wait.is_zero() {
+                        debug!("Readiness probe timed out on process \"{}\"", cloned_name);
+
+                        break;
+                    }
+
+                    if !matches!(
+                        stop_receiver.recv_timeout(wait),
+                        Err(RecvTimeoutError::Timeout)
+                    ) {
+                        return;
+                    }
+                }
+
+                // Keep accepting output until the process exits so that forwarding it does not
+                // fail.
+                if let Some(log_receiver) = &check.log_receiver {
+                    for _ in log_receiver.iter() {}
+                }
+            })
+            .map_err(Error::new)?;
+
+        Ok(Prober {
+            deadline,
+            is_ready,
+            log_sender,
+            _stop_sender: stop_sender,
+        })
+    }
+}
+
+/// Environment in which a process attempt is probed.
+pub struct ProbeTarget {
+    pub bin_command: BinCommand,
+    pub cwd: String,
+    pub env: HashMap<String, String>,
+}
+
+/// Handle to a running readiness probe.
+pub struct Prober {
+    deadline: Option<Instant>,
+    is_ready: Arc<AtomicBool>,
+    log_sender: Option<Sender<LogMonitorMessage>>,
+    _stop_sender: Sender<()>,
+}
+
+impl Prober {
+    /// Returns the deadline by which the probe must pass, unless it has already passed.
+    pub fn deadline(&self) -> Option<Instant> {
+        match self.is_ready() {
+            true => None,
+            false => self.deadline,
+        }
+    }
+
+    /// Returns whether the probe has passed.
+    pub fn is_ready(&self) -> bool {
+        self.is_ready.load(Ordering::SeqCst)
+    }
+
+    /// Returns the sender on which process output should be forwarded to the probe, if the probe
+    /// inspects output.
+    pub fn log_sender(&self) -> Option<&Sender<LogMonitorMessage>> {
+        self.log_sender.as_ref()
+    }
+}
+
+struct Check {
+    log_receiver: Option<Receiver<LogMonitorMessage>>,
+    pattern: Option<Regex>,
+    probe: Probe,
+    target: ProbeTarget,
+}
+
+impl Check {
+    fn passes(&self) -> bool {
+        match &self.probe {
+            Probe::Command(command) => {
+                let BinCommand { bin, mut args } = self.target.bin_command.clone();
+                args.push(command.clone());
+
+                Command::new(bin)
+                    .args(args)
+                    .current_dir(&self.target.cwd[..])
+                    .envs(&self.target.env)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok_and(|status| status.success())
+            }
+            Probe::File(path) => Path::new(&self.target.cwd).join(path).exists(),
+            Probe::Log(_) => {
+                let (receiver, pattern) = match (&self.log_receiver, &self.pattern) {
+                    (Some(receiver), Some(pattern)) => (receiver, pattern),
+                    _ => return false,
+                };
+
+                receiver.try_iter().any(|message| {
+                    matches!(message.cmd, LogMonitorCmd::Log) && pattern.is_match(&message.message)
+                })
+            }
+            Probe::Port(host, port) => {
+                (host.as_str(), *port)
+                    .to_socket_addrs()
+                    .is_ok_and(|mut addrs| {
+                        addrs.any(|addr| {
+                            TcpStream::connect_timeout(&addr, Duration::from_secs(1)).is_ok()
+                        })
+                    })
+            }
+        }
+    }
+}
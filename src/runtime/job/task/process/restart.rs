@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+/// Policy determining whether a process is restarted once its `command` exits.
+///
+/// `Never` leaves the process exited, `OnFailure` restarts it if it fails or times out, and
+/// `Always` restarts it regardless of how it exits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+/// Restart policy of a long-running process.
+///
+/// The process waits `delay` before each restart, grown by its backoff strategy with each restart
+/// made within `window`. If `max_restarts` restarts have already been made within `window`, or at
+/// all if no window is provided, the process is considered to be crash looping and is not
+/// restarted again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Restart {
+    pub delay: Duration,
+    pub max_restarts: Option<u32>,
+    pub policy: RestartPolicy,
+    pub window: Option<Duration>,
+}
+
+impl Default for Restart {
+    fn default() -> Self {
+        Self::new(RestartPolicy::Never)
+    }
+}
+
+impl Restart {
+    /// Constructs a new `Restart` from the specified policy.
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            delay: Duration::from_secs(1),
+            max_restarts: None,
+            policy,
+            window: None,
+        }
+    }
+
+    /// Builds `Restart` with the specified base delay before each restart.
+    pub fn delay(mut self, d: Duration) -> Self {
+        self.delay = d;
+
+        self
+    }
+
+    /// Builds `Restart` with the maximum number of restarts within `window`.
+    pub fn max_restarts(mut self, m: Option<u32>) -> Self {
+        self.max_restarts = m;
+
+        self
+    }
+
+    /// Builds `Restart` with the window within which restarts are counted.
+    pub fn window(mut self, w: Option<Duration>) -> Self {
+        self.window = w;
+
+        self
+    }
+
+    /// Returns whether a process which exited with the specified outcome should be restarted.
+    pub fn applies(&self, succeeded: bool) -> bool {
+        match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => !succeeded,
+            RestartPolicy::Always => true,
+        }
+    }
+
+    /// Begins tracking restarts against the restart budget.
+    pub fn budget(&self) -> RestartBudget {
+        RestartBudget {
+            max_restarts: self.max_restarts,
+            restarts: Vec::new(),
+            total: 0,
+            window: self.window,
+        }
+    }
+}
+
+/// Record of the restarts made by a process.
+#[derive(Debug)]
+pub struct RestartBudget {
+    max_restarts: Option<u32>,
+    restarts: Vec<Instant>,
+    total: u32,
+    window: Option<Duration>,
+}
+
+impl RestartBudget {
+    /// Records a restart, unless the budget is exhausted. Returns the number of restarts made
+    /// within the window, including this one, or `None` if the budget is exhausted.
+    pub fn spend(&mut self) -> Option<u32> {
+        let now = Instant::now();
+
+        if let Some(window) = self.window {
+            self.restarts
+                .retain(|restart| now.duration_since(*restart) < window);
+        }
+
+        if let Some(max_restarts) = self.max_restarts {
+            if self.restarts.len() >= max_restarts as usize {
+                return None;
+            }
+        }
+
+        self.restarts.push(now);
+        self.total += 1;
+
+        Some(self.restarts.len() as u32)
+    }
+
+    /// Returns the total number of restarts made.
+    pub fn total(&self) -> u32 {
+        self.total
+    }
+}
@@ -0,0 +1,102 @@
+use log::error;
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::{
+    fs::File,
+    io::{self, Write},
+    process::{Child, Command, Stdio},
+    thread::spawn,
+};
+
+/// Source from which a process `command` reads its stdin.
+///
+/// `Null` provides no input, `Inherit` attaches the stdin of arpx itself, `File` reads from the
+/// file at the given path, and `Text` writes the given text and then closes stdin.
+///
+/// A process which inherits stdin from a terminal is brought to the foreground of that terminal
+/// while it runs, so it should be the only process running at the time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Stdin {
+    #[default]
+    Null,
+    Inherit,
+    File(String),
+    Text(String),
+}
+
+impl Stdin {
+    /// Configures the stdin of the command.
+    pub fn attach(&self, command: &mut Command) -> io::Result<()> {
+        match self {
+            Self::Null => {
+                command.stdin(Stdio::null());
+            }
+            Self::Inherit => {
+                command.stdin(Stdio::inherit());
+
+                #[cfg(unix)]
+                if is_terminal() {
+                    unsafe {
+                        command.pre_exec(|| {
+                            set_terminal_foreground(libc::getpgrp());
+
+                            Ok(())
+                        });
+                    }
+                }
+            }
+            Self::File(path) => {
+                command.stdin(Stdio::from(File::open(path)?));
+            }
+            Self::Text(_) => {
+                command.stdin(Stdio::piped());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes any input for the spawned child to its stdin.
+    ///
+    /// Input is written on a separate thread so that a child which does not read all of its input
+    /// cannot block the runtime.
+    pub fn feed(&self, child: &mut Child) {
+        if let (Self::Text(text), Some(mut stdin)) = (self, child.stdin.take()) {
+            let text = text.clone();
+
+            spawn(move || {
+                if let Err(error) = stdin.write_all(text.as_bytes()) {
+                    if error.kind() != io::ErrorKind::BrokenPipe {
+                        error!("Error writing process stdin: {}", error);
+                    }
+                }
+            });
+        }
+    }
+
+    /// Returns the terminal to arpx once the child has exited, if the child inherited it.
+    pub fn release(&self) {
+        #[cfg(unix)]
+        if *self == Self::Inherit && is_terminal() {
+            set_terminal_foreground(unsafe { libc::getpgrp() });
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_terminal() -> bool {
+    unsafe { libc::isatty(libc::STDIN_FILENO) == 1 }
+}
+
+/// Makes the specified process group the foreground process group of the terminal on stdin.
+///
+/// `SIGTTOU` is ignored while doing so, since the caller is usually in a background process group
+/// and would otherwise be stopped.
+#[cfg(unix)]
+fn set_terminal_foreground(pgrp: libc::pid_t) {
+    unsafe {
+        let handler = libc::signal(libc::SIGTTOU, libc::SIG_IGN);
+        libc::tcsetpgrp(libc::STDIN_FILENO, pgrp);
+        libc::signal(libc::SIGTTOU, handler);
+    }
+}
@@ -1,14 +1,21 @@
-use crate::runtime::job::task::log_monitor::message::{LogMonitorCmd, LogMonitorMessage};
+use super::{capture::Capturer, encoding::Encoding, pty::Pty};
+use crate::runtime::{
+    ansi,
+    job::task::log_monitor::message::{LogMonitorCmd, LogMonitorMessage, OutputStream},
+};
 use anyhow::{bail, Context, Result};
 use crossbeam_channel::{unbounded, Receiver, Select, Sender};
 use log::{error, info};
-use std::{io, process::Child, string::FromUtf8Error, thread::spawn};
+use std::{
+    borrow::Cow,
+    io::{self, BufRead, BufReader},
+    process::Child,
+    thread::spawn,
+    time::Instant,
+};
 
-#[derive(Debug)]
-pub enum PipeError {
-    IO(io::Error),
-    NotUtf8(FromUtf8Error),
-}
+/// Capacity of the buffer in which output is read from a child.
+const READ_BUFFER_SIZE: usize = 64 * 1024;
 
 #[derive(Debug)]
 pub enum PipedLine {
@@ -16,102 +23,245 @@ pub enum PipedLine {
     Eof,
 }
 
+/// Reader of the lines written to an output stream of a child, on a separate thread.
+///
+/// Output is read in large chunks and split into lines, each decoded using the specified
+/// encoding. Lines longer than `max_line_length` bytes are truncated. A final line which does
+/// not end with a newline is still read once the stream closes.
+///
+/// A carriage return which is not followed by a newline overwrites the line, as it would on a
+/// terminal, so that a redrawn line, such as a progress bar, is read as its final state.
 #[derive(Debug)]
 pub struct PipeStreamReader {
-    pub lines: Receiver<Result<PipedLine, PipeError>>,
+    pub lines: Receiver<io::Result<PipedLine>>,
 }
 
 impl PipeStreamReader {
-    pub fn init(mut stream: Box<dyn io::Read + Send>) -> Self {
-        Self {
-            lines: {
-                let (tx, rx) = unbounded();
-
-                spawn(move || {
-                    let mut buf = Vec::new();
-                    let mut byte = [0u8];
-                    loop {
-                        match stream.read(&mut byte) {
-                            Ok(0) => {
-                                if tx.send(Ok(PipedLine::Eof)).is_err() {}
-
-                                break;
-                            }
-                            Ok(_) => {
-                                if byte[0] == 0x0A {
-                                    if let Err(error) =
-                                        tx.send(match String::from_utf8(buf.clone()) {
-                                            Ok(line) => Ok(PipedLine::Line(line)),
-                                            Err(err) => Err(PipeError::NotUtf8(err)),
-                                        })
-                                    {
-                                        error!("Output stream decoding error: {:#?}", error);
-                                    }
+    pub fn init(
+        stream: Box<dyn io::Read + Send>,
+        encoding: Encoding,
+        max_line_length: usize,
+    ) -> Self {
+        let (tx, rx) = unbounded();
 
-                                    buf.clear();
-                                } else {
-                                    buf.push(byte[0]);
-                                }
-                            }
-                            Err(error) => {
-                                if let Err(error) = tx.send(Err(PipeError::IO(error))) {
-                                    error!("Output stream error: {:#?}", error);
-                                }
-                            }
+        spawn(move || {
+            let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, stream);
+            let mut line = Vec::new();
+            let mut truncated = false;
+            let mut carriage_return = false;
+
+            loop {
+                let available = match reader.fill_buf() {
+                    Ok([]) => {
+                        if !line.is_empty() || truncated {
+                            tx.send(Ok(PipedLine::Line(encoding.decode(&line)))).ok();
                         }
+
+                        tx.send(Ok(PipedLine::Eof)).ok();
+
+                        break;
                     }
-                });
+                    Ok(available) => available,
+                    Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(error) => {
+                        tx.send(Err(error)).ok();
+                        tx.send(Ok(PipedLine::Eof)).ok();
 
-                rx
-            },
-        }
+                        break;
+                    }
+                };
+                let boundary = available
+                    .iter()
+                    .position(|byte| *byte == b'\n' || *byte == b'\r');
+                let end = boundary.unwrap_or(available.len());
+                let delimiter = boundary.map(|boundary| available[boundary]);
+
+                if end > 0 && carriage_return {
+                    line.clear();
+                    truncated = false;
+                    carriage_return = false;
+                }
+
+                if !truncated {
+                    let room = max_line_length.saturating_sub(line.len());
+
+                    line.extend_from_slice(&available[..end.min(room)]);
+                    truncated = end > room;
+                }
+
+                reader.consume(boundary.map_or(end, |boundary| boundary + 1));
+
+                match delimiter {
+                    Some(b'\n') => {
+                        // The receiver only disconnects once streaming has failed, at which point
+                        // the output is drained so that the child does not block on a full pipe.
+                        tx.send(Ok(PipedLine::Line(encoding.decode(&line)))).ok();
+
+                        line.clear();
+                        truncated = false;
+                        carriage_return = false;
+                    }
+                    Some(_) => carriage_return = true,
+                    None => {}
+                }
+            }
+        });
+
+        Self { lines: rx }
     }
+}
 
-    pub fn stream_child_output(
-        child: &mut Child,
-        log_monitor_senders: &[Sender<LogMonitorMessage>],
-    ) -> Result<()> {
+/// Reason for which streaming of child output stopped.
+#[derive(Debug, PartialEq, Eq)]
+pub enum StreamEnd {
+    Closed,
+    Deadline,
+    Interrupted,
+}
+
+/// Output streams of a running child, forwarded to the runtime logs and to log monitors.
+///
+/// When the child is attached to a pseudo-terminal, its stdout and stderr are read as a single
+/// stream from the terminal, which is treated as stdout.
+pub struct ChildOutput {
+    channels: Vec<PipeStreamReader>,
+    open: Vec<bool>,
+    strip_ansi: bool,
+    _pty: Option<Pty>,
+}
+
+impl ChildOutput {
+    /// Takes the stdout and stderr pipes of the child and begins reading from them.
+    pub fn new(child: &mut Child, encoding: Encoding, max_line_length: usize) -> Result<Self> {
         let channels = vec![
-            Self::init(Box::new(
-                child
-                    .stdout
-                    .take()
-                    .context("Error building stdout channel")?,
-            )),
-            Self::init(Box::new(
-                child
-                    .stderr
-                    .take()
-                    .context("Error building stderr channel")?,
-            )),
+            PipeStreamReader::init(
+                Box::new(
+                    child
+                        .stdout
+                        .take()
+                        .context("Error building stdout channel")?,
+                ),
+                encoding,
+                max_line_length,
+            ),
+            PipeStreamReader::init(
+                Box::new(
+                    child
+                        .stderr
+                        .take()
+                        .context("Error building stderr channel")?,
+                ),
+                encoding,
+                max_line_length,
+            ),
         ];
+        let open = vec![true; channels.len()];
+
+        Ok(Self {
+            channels,
+            open,
+            strip_ansi: false,
+            _pty: None,
+        })
+    }
 
+    /// Begins reading from the pseudo-terminal to which the child is attached.
+    pub fn pty(pty: Pty, encoding: Encoding, max_line_length: usize) -> Result<Self> {
+        let channels = vec![PipeStreamReader::init(
+            Box::new(pty.reader().context("Error building pty channel")?),
+            encoding,
+            max_line_length,
+        )];
+        let open = vec![true; channels.len()];
+
+        Ok(Self {
+            channels,
+            open,
+            strip_ansi: false,
+            _pty: Some(pty),
+        })
+    }
+
+    /// Builds `ChildOutput` with whether to strip ANSI escape sequences from the output logged by
+    /// the runtime.
+    pub fn strip_ansi(mut self, s: bool) -> Self {
+        self.strip_ansi = s;
+
+        self
+    }
+
+    /// Streams output from the child until all of its output pipes close, collecting its stdout in
+    /// the capturer, if provided.
+    ///
+    /// Streaming stops early if the deadline passes or if the interrupt receiver disconnects.
+    /// Streaming can be resumed by calling this method again.
+    pub fn stream(
+        &mut self,
+        log_monitor_senders: &[Sender<LogMonitorMessage>],
+        deadline: Option<Instant>,
+        interrupt: Option<&Receiver<()>>,
+        mut capturer: Option<&mut Capturer>,
+    ) -> Result<StreamEnd> {
         let mut select = Select::new();
+        let mut channel_indexes = Vec::new();
 
-        for channel in &channels {
-            select.recv(&channel.lines);
+        for (i, channel) in self.channels.iter().enumerate() {
+            if self.open[i] {
+                let index = select.recv(&channel.lines);
+                channel_indexes.push((index, i));
+            }
         }
 
-        let mut stream_eof = false;
+        let interrupt_index = interrupt.map(|receiver| select.recv(receiver));
 
-        while !stream_eof {
-            let operation = select.select();
+        while self.open.contains(&true) {
+            let operation = match deadline {
+                Some(deadline) => match select.select_deadline(deadline) {
+                    Ok(operation) => operation,
+                    Err(_) => return Ok(StreamEnd::Deadline),
+                },
+                None => select.select(),
+            };
             let index = operation.index();
-            let received = operation.recv(
-                channels
-                    .get(index)
-                    .context("Error selecting stream channel")?
-                    .map(|channel| &channel.lines),
-            );
+
+            if let (Some(interrupt), Some(interrupt_index)) = (interrupt, interrupt_index) {
+                if index == interrupt_index {
+                    operation.recv(interrupt).ok();
+
+                    return Ok(StreamEnd::Interrupted);
+                }
+            }
+
+            let channel = channel_indexes
+                .iter()
+                .find(|(select_index, _)| *select_index == index)
+                .map(|(_, channel)| *channel)
+                .context("Error selecting stream channel")?;
+            let received = operation.recv(&self.channels[channel].lines);
 
             if let Ok(remote_result) = received {
                 match remote_result {
                     Ok(piped_line) => match piped_line {
                         PipedLine::Line(line) => {
-                            if index == 0 {
-                                info!("{}", line);
-                            } else {
-                                error!("{}", line);
+                            let stream = match channel {
+                                0 => OutputStream::Stdout,
+                                _ => OutputStream::Stderr,
+                            };
+
+                            let display = match self.strip_ansi {
+                                true => ansi::strip(&line),
+                                false => Cow::Borrowed(&line[..]),
+                            };
+
+                            match stream {
+                                OutputStream::Stdout => {
+                                    info!("{}", display);
+
+                                    if let Some(capturer) = capturer.as_deref_mut() {
+                                        capturer.push(&line);
+                                    }
+                                }
+                                OutputStream::Stderr => error!("{}", display),
                             }
 
                             for sender in log_monitor_senders.iter() {
@@ -119,7 +269,8 @@ impl PipeStreamReader {
                                     .send(
                                         LogMonitorMessage::new()
                                             .cmd(LogMonitorCmd::Log)
-                                            .message(line.clone()),
+                                            .message(line.clone())
+                                            .stream(stream),
                                     )
                                     .is_err()
                                 {
@@ -128,27 +279,20 @@ impl PipeStreamReader {
                             }
                         }
                         PipedLine::Eof => {
-                            stream_eof = true;
+                            self.open[channel] = false;
                             select.remove(index);
                         }
                     },
                     Err(error) => {
-                        error!("Error streaming process output: {:?}", error);
+                        error!("Error streaming process output: {}", error);
                     }
                 }
             } else {
-                stream_eof = true;
+                self.open[channel] = false;
                 select.remove(index);
             }
         }
 
-        Ok(())
-    }
-
-    pub fn map<'a, F, B>(&'a self, map_fn: F) -> B
-    where
-        F: Fn(&'a Self) -> B,
-    {
-        map_fn(self)
+        Ok(StreamEnd::Closed)
     }
 }
@@ -1,7 +1,13 @@
+pub mod ansi;
 pub mod ctx;
 pub mod job;
 pub mod local_bin;
 pub mod profile;
+pub mod registry;
+pub mod scope;
+pub mod shutdown;
+pub mod signal;
+pub mod vars;
 
 use crate::runtime::job::task::{log_monitor::LogMonitor, process::Process};
 use anyhow::{Context, Result};
@@ -10,7 +16,12 @@ use job::Job;
 use local_bin::BinCommand;
 use log::debug;
 use profile::Profile;
-use std::collections::HashMap;
+use registry::ProcessRegistry;
+use scope::Scope;
+use shutdown::{ExitError, Shutdown};
+use signal::SignalHandlers;
+use std::{collections::HashMap, time::Duration};
+use vars::Vars;
 
 /// Represents and contains a given runtime.
 ///
@@ -65,8 +76,16 @@ use std::collections::HashMap;
 /// Once all tasks in a given job have completed their execution, the runtime moves on to the next
 /// job in the queue. Once all jobs have completed their execution, the runtime is finished.
 ///
+/// The runtime shuts down early if a builtin exit action (`arpx_exit`, `arpx_exit_error`, or
+/// `arpx_exit(<code>)`) is performed or if a termination signal (`SIGINT`, `SIGTERM`, or `SIGQUIT`)
+/// is received. Running processes, along with any children they have spawned, are then sent their
+/// stop signal and killed if they are still running after their stop timeout. Unless configured on
+/// the process, the stop signal is the received signal or `SIGTERM`, and the stop timeout is the
+/// [`grace_period`]. No further tasks, jobs, or actions are executed.
+///
 /// [`jobs`]: #structfield.jobs
 /// [`ctx`]: #structfield.ctx
+/// [`grace_period`]: #method.grace_period
 ///
 /// # Examples:
 ///
@@ -79,7 +98,7 @@ use std::collections::HashMap;
 /// let processes = vec![
 ///     Process::new("p_foo".to_string())
 ///         .command("echo foo".to_string())
-///         .onsucceed(Some("p_baz".to_string())),
+///         .onsucceed(vec!["p_baz".to_string()]),
 ///     Process::new("p_bar".to_string()).command("echo bar".to_string()),
 /// ];
 ///
@@ -171,6 +190,49 @@ impl Runtime {
         self
     }
 
+    /// Builds `Runtime` with the directory of the profile from which it was loaded.
+    ///
+    /// This is exposed to processes and log monitor tests as `ARPX_PROFILE_DIR`.
+    #[must_use]
+    pub fn profile_dir(mut self, d: Option<String>) -> Self {
+        self.ctx.scope.profile_dir = d;
+
+        self
+    }
+
+    /// Builds `Runtime` with whether to strip ANSI escape sequences from process output.
+    ///
+    /// This is useful when the runtime output is not displayed on a terminal. Output forwarded to
+    /// log monitors is not affected.
+    #[must_use]
+    pub fn strip_ansi(mut self, s: bool) -> Self {
+        self.ctx.strip_ansi = s;
+
+        self
+    }
+
+    /// Builds `Runtime` with the specified grace period.
+    ///
+    /// When the runtime is interrupted, running processes are given this long to exit before they
+    /// are killed. Defaults to 10 seconds.
+    #[must_use]
+    pub fn grace_period(mut self, g: Duration) -> Self {
+        self.ctx.grace_period = g;
+
+        self
+    }
+
+    /// Builds `Runtime` with the specified maximum action depth.
+    ///
+    /// This is the maximum length of a chain of actions, each executed by the exit of the last,
+    /// before the runtime shuts down with an error. Defaults to 1000.
+    #[must_use]
+    pub fn max_action_depth(mut self, m: usize) -> Self {
+        self.ctx.max_action_depth = m;
+
+        self
+    }
+
     /// Constructs a new `Runtime` from a profile at the specified path, using the specified jobs.
     pub fn from_profile(path: &str, job_names: &[String]) -> Result<Self> {
         debug!("Loading runtime from profile");
@@ -179,12 +241,41 @@ impl Runtime {
     }
 
     /// Executes the runtime.
+    ///
+    /// If the runtime shuts down with a non-zero exit status, an [`ExitError`] containing that
+    /// status is returned.
+    ///
+    /// [`ExitError`]: crate::ExitError
     pub fn run(&self) -> Result<()> {
         debug!("Running runtime instance with structure:\n{:#?}", self);
 
-        self.jobs
+        let mut ctx = self.ctx.clone();
+        ctx.registry = ProcessRegistry::new();
+        ctx.scope = Scope {
+            profile_dir: self.ctx.scope.profile_dir.clone(),
+            ..Scope::new()
+        };
+        ctx.shutdown = Shutdown::new();
+        ctx.vars = Vars::new();
+
+        let signal_handlers = SignalHandlers::install(ctx.shutdown.clone(), ctx.registry.clone())?;
+
+        let result = self
+            .jobs
             .iter()
-            .try_for_each(|job| job.clone().run(&self.ctx.clone()))
-            .context("Runtime error")
+            .take_while(|_| !ctx.shutdown.is_triggered())
+            .try_for_each(|job| job.clone().run(&ctx))
+            .context("Runtime error");
+
+        signal_handlers.close();
+
+        match ctx.shutdown.code() {
+            Some(0) | None => result,
+            Some(code) => {
+                debug!("Runtime shut down with exit status {}", code);
+
+                Err(ExitError { code }.into())
+            }
+        }
     }
 }
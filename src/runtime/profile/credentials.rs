@@ -0,0 +1,105 @@
+use anyhow::{bail, Result};
+#[cfg(unix)]
+use anyhow::{ensure, Context};
+#[cfg(unix)]
+use std::{ffi::CString, io, mem, ptr};
+
+/// Resolves a user, given by name or numeric id, into its uid and, if the user exists, its
+/// primary gid.
+#[cfg(unix)]
+pub fn resolve_user(user: &str) -> Result<(u32, Option<u32>)> {
+    if let Ok(uid) = user.parse::<u32>() {
+        let gid = lookup_user(|pwd, buf, result| unsafe {
+            libc::getpwuid_r(uid, pwd, buf.as_mut_ptr(), buf.len(), result)
+        })?
+        .map(|(_, gid)| gid);
+
+        return Ok((uid, gid));
+    }
+
+    let name = CString::new(user).context(format!("Invalid user \"{}\"", user))?;
+    let (uid, gid) = lookup_user(|pwd, buf, result| unsafe {
+        libc::getpwnam_r(name.as_ptr(), pwd, buf.as_mut_ptr(), buf.len(), result)
+    })?
+    .context(format!("Unknown user \"{}\"", user))?;
+
+    Ok((uid, Some(gid)))
+}
+
+/// Resolves a group, given by name or numeric id, into its gid.
+#[cfg(unix)]
+pub fn resolve_group(group: &str) -> Result<u32> {
+    if let Ok(gid) = group.parse::<u32>() {
+        return Ok(gid);
+    }
+
+    let name = CString::new(group).context(format!("Invalid group \"{}\"", group))?;
+    let mut grp: libc::group = unsafe { mem::zeroed() };
+    let mut result = ptr::null_mut();
+    let mut buf = vec![0; 16384];
+
+    let code = unsafe {
+        libc::getgrnam_r(
+            name.as_ptr(),
+            &mut grp,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+
+    ensure!(
+        code == 0,
+        "Error looking up group \"{}\": {}",
+        group,
+        io::Error::from_raw_os_error(code)
+    );
+    ensure!(!result.is_null(), "Unknown group \"{}\"", group);
+
+    Ok(grp.gr_gid)
+}
+
+/// Looks up a user using the provided call to `getpwnam_r` or `getpwuid_r`. Returns the uid and
+/// primary gid of the user, if found.
+#[cfg(unix)]
+fn lookup_user(
+    getpw: impl Fn(*mut libc::passwd, &mut [libc::c_char], *mut *mut libc::passwd) -> libc::c_int,
+) -> Result<Option<(u32, u32)>> {
+    let mut pwd: libc::passwd = unsafe { mem::zeroed() };
+    let mut result = ptr::null_mut();
+    let mut buf = vec![0; 16384];
+
+    let code = getpw(&mut pwd, &mut buf, &mut result);
+
+    ensure!(
+        code == 0,
+        "Error looking up user: {}",
+        io::Error::from_raw_os_error(code)
+    );
+
+    Ok(match result.is_null() {
+        true => None,
+        false => Some((pwd.pw_uid, pwd.pw_gid)),
+    })
+}
+
+#[cfg(not(unix))]
+pub fn resolve_user(_user: &str) -> Result<(u32, Option<u32>)> {
+    bail!("Running processes as another user is not supported on this platform")
+}
+
+#[cfg(not(unix))]
+pub fn resolve_group(_group: &str) -> Result<u32> {
+    bail!("Running processes as another group is not supported on this platform")
+}
+
+/// Parses a umask given as octal digits, such as `022`.
+pub fn parse_umask(umask: &str) -> Result<u32> {
+    match u32::from_str_radix(umask.trim(), 8) {
+        Ok(mask) if mask <= 0o777 => Ok(mask),
+        _ => bail!(
+            "Invalid umask \"{}\": expected octal digits such as 022",
+            umask
+        ),
+    }
+}
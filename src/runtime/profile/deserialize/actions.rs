@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Actions {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Default for Actions {
+    fn default() -> Self {
+        Self::Many(Vec::new())
+    }
+}
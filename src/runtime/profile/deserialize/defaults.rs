@@ -14,6 +14,10 @@ pub fn processes() -> HashMap<String, processes::Process> {
     HashMap::new()
 }
 
+pub fn backoff() -> processes::Backoff {
+    processes::Backoff::Fixed
+}
+
 pub fn cwd() -> String {
     ".".to_string()
 }
@@ -26,6 +30,14 @@ pub fn string_vec() -> Vec<String> {
     Vec::new()
 }
 
+pub fn string_map() -> HashMap<String, String> {
+    HashMap::new()
+}
+
 pub fn buffer_size() -> usize {
     20
 }
+
+pub fn strip_ansi() -> bool {
+    true
+}
@@ -1,15 +1,38 @@
-use crate::runtime::profile::deserialize::defaults;
+use crate::runtime::profile::deserialize::{actions::Actions, defaults};
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
 
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    #[default]
+    Both,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct LogMonitor {
+    #[serde(default = "defaults::string_vec")]
+    pub args: Vec<String>,
+    #[serde(default = "defaults::string")]
+    pub bin: String,
     #[serde(default = "defaults::buffer_size")]
     pub buffer_size: usize,
+    #[serde(default)]
+    pub concurrent_actions: bool,
+    #[serde(default = "defaults::string_map")]
+    pub env: HashMap<String, String>,
     #[serde(default = "defaults::string")]
-    pub name: String,
+    pub env_file: String,
     #[serde(default = "defaults::string")]
-    pub ontrigger: String,
+    pub name: String,
+    #[serde(default)]
+    pub ontrigger: Actions,
+    #[serde(default)]
+    pub stream: Stream,
+    #[serde(default = "defaults::strip_ansi")]
+    pub strip_ansi: bool,
     #[serde(default = "defaults::string")]
     pub test: String,
 }
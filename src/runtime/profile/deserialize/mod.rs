@@ -1,3 +1,4 @@
+pub mod actions;
 mod defaults;
 mod jobs;
 pub mod log_monitors;
@@ -1,21 +1,202 @@
-use crate::runtime::profile::deserialize::defaults;
+use crate::runtime::profile::deserialize::{actions::Actions, defaults};
 use serde::{Deserialize, Deserializer};
 use std::collections::HashMap;
 
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backoff {
+    Fixed,
+    Exponential,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum ByteSize {
+    Bytes(u64),
+    Text(String),
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureFrom {
+    #[default]
+    Stdout,
+    LastLine,
+    Regex,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Capture {
+    #[serde(default)]
+    pub from: CaptureFrom,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default = "defaults::string")]
+    pub var: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Command {
+    Shell(String),
+    Argv(Vec<String>),
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Self::Shell(String::new())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    #[serde(rename = "utf-8", alias = "utf8")]
+    Utf8,
+    #[serde(rename = "latin-1", alias = "latin1", alias = "iso-8859-1")]
+    Latin1,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Umask {
+    Number(u32),
+    Text(String),
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    #[serde(default)]
+    pub address_space: Option<ByteSize>,
+    #[serde(default)]
+    pub core_size: Option<ByteSize>,
+    #[serde(default)]
+    pub cpu: Option<u64>,
+    #[serde(default)]
+    pub open_files: Option<u64>,
+    #[serde(default)]
+    pub processes: Option<u64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct ReadyWhen {
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub interval: Option<f64>,
+    #[serde(default)]
+    pub log: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub timeout: Option<f64>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Restart {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Stdin {
+    Null,
+    Inherit,
+    File(String),
+    Text(String),
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum ExitKey {
+    Code(i32),
+    Condition(String),
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct Process {
+    #[serde(default = "defaults::string_vec")]
+    pub args: Vec<String>,
+    #[serde(default = "defaults::backoff")]
+    pub backoff: Backoff,
+    #[serde(default)]
+    pub backoff_cap: Option<f64>,
+    #[serde(default = "defaults::string")]
+    pub bin: String,
+    #[serde(default)]
+    pub capture: Option<Capture>,
+    #[serde(default = "defaults::string")]
+    pub group: String,
+    #[serde(default)]
+    pub limits: Option<Limits>,
     #[serde(default = "defaults::string_vec")]
     pub log_monitors: Vec<String>,
+    #[serde(default)]
+    pub max_line_length: Option<ByteSize>,
+    #[serde(default)]
+    pub max_restarts: Option<u32>,
     #[serde(default = "defaults::string")]
     pub name: String,
-    #[serde(default = "defaults::string")]
-    pub command: String,
+    #[serde(default)]
+    pub command: Command,
+    #[serde(default)]
+    pub concurrent_actions: bool,
     #[serde(default = "defaults::cwd")]
     pub cwd: String,
+    #[serde(default = "defaults::string_vec")]
+    pub depends_on: Vec<String>,
+    #[serde(default)]
+    pub encoding: Encoding,
+    #[serde(default = "defaults::string_map")]
+    pub env: HashMap<String, String>,
+    #[serde(default = "defaults::string")]
+    pub env_file: String,
+    #[serde(default)]
+    pub expand_env: bool,
+    #[serde(default)]
+    pub oncrashloop: Actions,
+    #[serde(default)]
+    pub onexit: HashMap<ExitKey, Actions>,
+    #[serde(default)]
+    pub onsucceed: Actions,
+    #[serde(default)]
+    pub onfail: Actions,
+    #[serde(default)]
+    pub ontimeout: Actions,
+    #[serde(default)]
+    pub ready_when: Option<ReadyWhen>,
+    #[serde(default)]
+    pub restart: Restart,
+    #[serde(default)]
+    pub restart_delay: Option<f64>,
+    #[serde(default)]
+    pub restart_window: Option<f64>,
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default)]
+    pub retry_delay: Option<f64>,
+    #[serde(default)]
+    pub stdin: Option<Stdin>,
     #[serde(default = "defaults::string")]
-    pub onsucceed: String,
+    pub stop_signal: String,
+    #[serde(default)]
+    pub stop_timeout: Option<f64>,
+    #[serde(default)]
+    pub timeout: Option<f64>,
+    #[serde(default)]
+    pub tty: bool,
+    #[serde(default)]
+    pub umask: Option<Umask>,
     #[serde(default = "defaults::string")]
-    pub onfail: String,
+    pub user: String,
 }
 
 pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<String, Process>, D::Error>
@@ -0,0 +1,44 @@
+use anyhow::{ensure, Context, Result};
+use std::{collections::HashMap, fs};
+
+/// Loads environment variables from a dotenv-style file at the specified path.
+///
+/// Each line is parsed as `KEY=VALUE`. Blank lines and lines beginning with `#` are ignored, a
+/// leading `export ` is stripped, and values wrapped in matching single or double quotes are
+/// unquoted.
+pub fn load(path: &str) -> Result<HashMap<String, String>> {
+    let data = fs::read_to_string(path).context(format!("Error reading env_file \"{}\"", path))?;
+
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=').context(format!(
+                "Invalid env_file \"{}\", line {}: expected KEY=VALUE",
+                path, line_number
+            ))?;
+            let key = key.trim();
+
+            ensure!(
+                !key.is_empty(),
+                "Invalid env_file \"{}\", line {}: empty key",
+                path,
+                line_number
+            );
+
+            Ok((key.to_owned(), unquote(value.trim()).to_owned()))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+
+    value
+}
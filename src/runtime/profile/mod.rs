@@ -1,4 +1,6 @@
+mod credentials;
 mod deserialize;
+mod env_file;
 mod runtime_builder;
 
 use crate::runtime::Runtime;
@@ -6,7 +8,7 @@ use anyhow::{Context, Error, Result};
 pub use deserialize::Profile;
 use log::debug;
 use runtime_builder::RuntimeBuilder;
-use std::fs;
+use std::{fs, path::Path};
 
 /// Represents and contains a runtime object defined by a profile.
 ///
@@ -74,8 +76,14 @@ impl Profile {
         let data = fs::read_to_string(path).context("Error reading file")?;
         let profile = Self::deserialize_from_str(&data).context("Error deserializing file")?;
 
+        let profile_dir = Path::new(path)
+            .canonicalize()
+            .ok()
+            .and_then(|path| path.parent().map(|dir| dir.display().to_string()));
+
         RuntimeBuilder::from_profile_and_job_names(profile, job_names)
             .context("Error building runtime")
+            .map(|runtime| runtime.profile_dir(profile_dir))
     }
 
     fn deserialize_from_str(data: &str) -> Result<Self> {
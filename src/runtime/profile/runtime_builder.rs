@@ -1,14 +1,36 @@
 use crate::runtime::{
     job::{
-        task::{action::BUILTIN_ACTIONS, log_monitor::LogMonitor, process::Process, Task},
+        task::{
+            action::Builtin,
+            log_monitor::{message::OutputStream, LogMonitor},
+            process::{
+                backoff::Backoff,
+                capture::{Capture, CaptureMode},
+                encoding::Encoding,
+                exit_condition::ExitCondition,
+                limits::Limits,
+                readiness::{Probe, Readiness},
+                restart::{Restart, RestartPolicy},
+                stdin::Stdin,
+                Process,
+            },
+            Task,
+        },
         Job,
     },
-    profile::{deserialize, Profile},
-    Runtime,
+    local_bin::BinCommand,
+    profile::{credentials, deserialize, env_file, Profile},
+    signal, Runtime,
 };
-use anyhow::{ensure, Context, Error, Result};
+use anyhow::{bail, ensure, Context, Error, Result};
 use log::debug;
-use std::{collections::HashMap, env::var, path::Path};
+use regex::Regex;
+use std::{
+    collections::{HashMap, HashSet},
+    env::var,
+    path::Path,
+    time::Duration,
+};
 
 pub struct RuntimeBuilder;
 
@@ -18,7 +40,7 @@ impl RuntimeBuilder {
 
         debug!("Building log_monitor_map");
 
-        let log_monitor_map = Self::build_log_monitor_map(profile.log_monitors);
+        let log_monitor_map = Self::build_log_monitor_map(profile.log_monitors)?;
 
         ensure!(
             log_monitor_map.len()
@@ -47,6 +69,15 @@ impl RuntimeBuilder {
             "No valid processes exist in profile"
         );
 
+        for log_monitor in log_monitor_map.values() {
+            Self::check_actions(
+                &format!("Log monitor \"{}\"", log_monitor.name),
+                "ontrigger",
+                &log_monitor.ontrigger,
+                &process_map,
+            )?;
+        }
+
         debug!("Building jobs object");
 
         ensure!(!job_names.is_empty(), "No jobs requested for runtime");
@@ -58,13 +89,18 @@ impl RuntimeBuilder {
                     .get(&job_name[..])
                     .context(format!("Requested job \"{}\" not defined in jobs", job_name))?;
 
-                return Ok(Job::new(
+                Ok(Job::new(
                     job_name.into(),
                     job.tasks
                         .iter()
                         .enumerate()
                         .map(|(i, task)| {
                             let task_index = i + 1;
+                            let scheduled = job.tasks[..=i]
+                                .iter()
+                                .flat_map(|task| &task.processes)
+                                .map(|process| &process.name[..])
+                                .collect::<HashSet<&str>>();
 
                             ensure!(
                                 task.processes.len()
@@ -77,7 +113,7 @@ impl RuntimeBuilder {
                                 task_index
                             );
 
-                            return Ok(Task::new(
+                            Ok(Task::new(
                                 task.processes
                                     .iter()
                                     .map(|process| {
@@ -89,6 +125,17 @@ impl RuntimeBuilder {
                                                 process.name
                                             ))?;
 
+                                        for dependency in &default_process.depends_on {
+                                            ensure!(
+                                                scheduled.contains(&dependency[..]),
+                                                "Job \"{}\", task {}: process \"{}\" depends on \"{}\", which does not run in the same task or an earlier one",
+                                                job_name,
+                                                task_index,
+                                                process.name,
+                                                dependency
+                                            );
+                                        }
+
                                         ensure!(
                                             task.processes.len() + process.log_monitors.len()
                                                 <= var("ARPX_THREAD_MAX")
@@ -110,37 +157,42 @@ impl RuntimeBuilder {
                                             );
                                         }
 
-                                        return Ok(Process::new(default_process.name.clone())
-                                            .command(default_process.command.clone())
-                                            .cwd(default_process.cwd.clone())
+                                        Ok(default_process
+                                            .clone()
                                             .log_monitors(process.log_monitors.clone())
                                             .onfail(match &process.onfail {
                                                 Some(onfail) => {
-                                                    ensure!(
-                                                        process_map.contains_key(onfail)
-                                                            || BUILTIN_ACTIONS.contains(&&onfail[..]),
-                                                        "Job \"{}\", task {}: invalid onfail \"{}\" provided",
-                                                        job_name,
-                                                        task_index,
-                                                        onfail
-                                                    );
-
-                                                    Some(onfail.into())
+                                                    let onfail = vec![onfail.clone()];
+
+                                                    Self::check_actions(
+                                                        &format!(
+                                                            "Job \"{}\", task {}",
+                                                            job_name, task_index
+                                                        ),
+                                                        "onfail",
+                                                        &onfail,
+                                                        &process_map,
+                                                    )?;
+
+                                                    onfail
                                                 }
                                                 None => default_process.onfail.clone(),
                                             })
                                             .onsucceed(match &process.onsucceed {
                                                 Some(onsucceed) => {
-                                                    ensure!(
-                                                        process_map.contains_key(onsucceed)
-                                                            || BUILTIN_ACTIONS.contains(&&onsucceed[..]),
-                                                        "Job \"{}\", task {}: invalid onsucceed \"{}\" provided",
-                                                        job_name,
-                                                        task_index,
-                                                        onsucceed
-                                                    );
-
-                                                    Some(onsucceed.into())
+                                                    let onsucceed = vec![onsucceed.clone()];
+
+                                                    Self::check_actions(
+                                                        &format!(
+                                                            "Job \"{}\", task {}",
+                                                            job_name, task_index
+                                                        ),
+                                                        "onsucceed",
+                                                        &onsucceed,
+                                                        &process_map,
+                                                    )?;
+
+                                                    onsucceed
                                                 }
                                                 None => default_process.onsucceed.clone(),
                                             }))
@@ -167,24 +219,38 @@ impl RuntimeBuilder {
 
     pub fn build_log_monitor_map(
         log_monitors: HashMap<String, deserialize::log_monitors::LogMonitor>,
-    ) -> HashMap<String, LogMonitor> {
+    ) -> Result<HashMap<String, LogMonitor>> {
         log_monitors
             .into_iter()
             .map(|(name, v)| {
+                let env = Self::build_env(&v.env_file, v.env)
+                    .context(format!("Invalid env on log monitor \"{}\"", name))?;
+                let bin_command = Self::build_bin_command(v.bin, v.args)
+                    .context(format!("Invalid bin on log monitor \"{}\"", name))?;
+
                 let log_monitor = LogMonitor::new(name.clone())
+                    .bin_command(bin_command)
                     .buffer_size(v.buffer_size)
-                    .ontrigger(v.ontrigger)
+                    .concurrent_actions(v.concurrent_actions)
+                    .env(env)
+                    .ontrigger(Self::build_actions(v.ontrigger))
+                    .stream(match v.stream {
+                        deserialize::log_monitors::Stream::Stdout => Some(OutputStream::Stdout),
+                        deserialize::log_monitors::Stream::Stderr => Some(OutputStream::Stderr),
+                        deserialize::log_monitors::Stream::Both => None,
+                    })
+                    .strip_ansi(v.strip_ansi)
                     .test(v.test);
 
-                (name, log_monitor)
+                Ok((name, log_monitor))
             })
-            .collect()
+            .collect::<Result<HashMap<String, LogMonitor>, Error>>()
     }
 
     pub fn build_process_map(
         processes: HashMap<String, deserialize::processes::Process>,
     ) -> Result<HashMap<String, Process>> {
-        processes
+        let process_map = processes
             .into_iter()
             .map(|(name, v)| {
                 ensure!(
@@ -194,21 +260,488 @@ impl RuntimeBuilder {
                     name
                 );
 
+                let stdin = match v.stdin {
+                    None | Some(deserialize::processes::Stdin::Null) => Stdin::Null,
+                    Some(deserialize::processes::Stdin::Inherit) => Stdin::Inherit,
+                    Some(deserialize::processes::Stdin::File(path)) => {
+                        ensure!(
+                            Path::new(&path).is_file(),
+                            "Configured stdin file \"{}\" on process \"{}\" is not a valid file",
+                            path,
+                            name
+                        );
+
+                        Stdin::File(path)
+                    }
+                    Some(deserialize::processes::Stdin::Text(text)) => Stdin::Text(text),
+                };
+
+                ensure!(
+                    !v.tty || stdin == Stdin::Null,
+                    "Configured stdin on process \"{}\" cannot be used with tty",
+                    name
+                );
+
+                let env = Self::build_env(&v.env_file, v.env)
+                    .context(format!("Invalid env on process \"{}\"", name))?;
+                let bin_command = Self::build_bin_command(v.bin, v.args)
+                    .context(format!("Invalid bin on process \"{}\"", name))?;
+                let (command, argv) = match v.command {
+                    deserialize::processes::Command::Shell(command) => (command, Vec::new()),
+                    deserialize::processes::Command::Argv(argv) => {
+                        ensure!(
+                            !argv.is_empty(),
+                            "Configured command on process \"{}\" must not be an empty list",
+                            name
+                        );
+                        ensure!(
+                            bin_command.is_none(),
+                            "Configured bin on process \"{}\" cannot be used with a command list",
+                            name
+                        );
+
+                        (String::new(), argv)
+                    }
+                };
+                let timeout = Self::build_duration(v.timeout)
+                    .context(format!("Invalid timeout on process \"{}\"", name))?;
+                let retry_delay = Self::build_duration(v.retry_delay)
+                    .context(format!("Invalid retry_delay on process \"{}\"", name))?;
+                let backoff_cap = Self::build_duration(v.backoff_cap)
+                    .context(format!("Invalid backoff_cap on process \"{}\"", name))?;
+                let restart_delay = Self::build_duration(v.restart_delay)
+                    .context(format!("Invalid restart_delay on process \"{}\"", name))?;
+                let restart_window = Self::build_duration(v.restart_window)
+                    .context(format!("Invalid restart_window on process \"{}\"", name))?;
+                let restart = Restart::new(match v.restart {
+                    deserialize::processes::Restart::Never => RestartPolicy::Never,
+                    deserialize::processes::Restart::OnFailure => RestartPolicy::OnFailure,
+                    deserialize::processes::Restart::Always => RestartPolicy::Always,
+                })
+                .max_restarts(v.max_restarts)
+                .window(restart_window);
+                let restart = match restart_delay {
+                    Some(restart_delay) => restart.delay(restart_delay),
+                    None => restart,
+                };
+                let stop_signal = match &v.stop_signal[..] {
+                    "" => None,
+                    name => Some(signal::from_name(name).context(format!(
+                        "Invalid stop_signal \"{}\" on process \"{}\"",
+                        name, v.name
+                    ))?),
+                };
+                let stop_timeout = Self::build_duration(v.stop_timeout)
+                    .context(format!("Invalid stop_timeout on process \"{}\"", name))?;
+                let onexit = Self::build_onexit(v.onexit)
+                    .context(format!("Invalid onexit on process \"{}\"", name))?;
+                let (user, user_group) = match &v.user[..] {
+                    "" => (None, None),
+                    user => {
+                        let (uid, gid) = credentials::resolve_user(user)
+                            .context(format!("Invalid user on process \"{}\"", name))?;
+
+                        (Some(uid), gid)
+                    }
+                };
+                let group = match &v.group[..] {
+                    "" if user.is_some() && user_group.is_none() => bail!(
+                        "Missing group on process \"{}\": user \"{}\" has no primary group, so group must be provided",
+                        name,
+                        v.user
+                    ),
+                    "" => user_group,
+                    group => Some(
+                        credentials::resolve_group(group)
+                            .context(format!("Invalid group on process \"{}\"", name))?,
+                    ),
+                };
+                let umask = match v.umask {
+                    None => None,
+                    Some(deserialize::processes::Umask::Number(umask)) => Some(umask.to_string()),
+                    Some(deserialize::processes::Umask::Text(umask)) => Some(umask),
+                }
+                .map(|umask| credentials::parse_umask(&umask))
+                .transpose()
+                .context(format!("Invalid umask on process \"{}\"", name))?;
+                let limits = v
+                    .limits
+                    .map(Self::build_limits)
+                    .transpose()
+                    .context(format!("Invalid limits on process \"{}\"", name))?
+                    .unwrap_or_default();
+                let capture = v
+                    .capture
+                    .map(Self::build_capture)
+                    .transpose()
+                    .context(format!("Invalid capture on process \"{}\"", name))?;
+                let max_line_length = v
+                    .max_line_length
+                    .map(Self::build_max_line_length)
+                    .transpose()
+                    .context(format!("Invalid max_line_length on process \"{}\"", name))?;
+                let ready_when = v
+                    .ready_when
+                    .map(Self::build_readiness)
+                    .transpose()
+                    .context(format!("Invalid ready_when on process \"{}\"", name))?;
+
                 let process = Process::new(name.clone())
-                    .command(v.command)
+                    .argv(argv)
+                    .backoff(match v.backoff {
+                        deserialize::processes::Backoff::Fixed => Backoff::Fixed,
+                        deserialize::processes::Backoff::Exponential => {
+                            Backoff::Exponential { cap: backoff_cap }
+                        }
+                    })
+                    .bin_command(bin_command)
+                    .capture(capture)
+                    .command(command)
+                    .concurrent_actions(v.concurrent_actions)
                     .cwd(v.cwd)
-                    .log_monitors(v.log_monitors)
-                    .onfail(match &v.onfail[..] {
-                        "" => None,
-                        _ => Some(v.onfail),
+                    .depends_on(v.depends_on)
+                    .encoding(match v.encoding {
+                        deserialize::processes::Encoding::Utf8 => Encoding::Utf8,
+                        deserialize::processes::Encoding::Latin1 => Encoding::Latin1,
                     })
-                    .onsucceed(match &v.onsucceed[..] {
-                        "" => None,
-                        _ => Some(v.onsucceed),
-                    });
+                    .env(env)
+                    .expand_env(v.expand_env)
+                    .group(group)
+                    .limits(limits)
+                    .log_monitors(v.log_monitors)
+                    .oncrashloop(Self::build_actions(v.oncrashloop))
+                    .onexit(onexit)
+                    .onfail(Self::build_actions(v.onfail))
+                    .onsucceed(Self::build_actions(v.onsucceed))
+                    .ontimeout(Self::build_actions(v.ontimeout))
+                    .ready_when(ready_when)
+                    .restart(restart)
+                    .retries(v.retries)
+                    .retry_delay(retry_delay.unwrap_or_default())
+                    .stdin(stdin)
+                    .stop_signal(stop_signal)
+                    .stop_timeout(stop_timeout)
+                    .timeout(timeout)
+                    .tty(v.tty)
+                    .umask(umask)
+                    .user(user);
+                let process = match max_line_length {
+                    Some(max_line_length) => process.max_line_length(max_line_length),
+                    None => process,
+                };
 
                 Ok((name, process))
             })
-            .collect::<Result<HashMap<String, Process>, Error>>()
+            .collect::<Result<HashMap<String, Process>, Error>>()?;
+
+        for process in process_map.values() {
+            for dependency in &process.depends_on {
+                ensure!(
+                    process_map.contains_key(dependency),
+                    "Process \"{}\": invalid depends_on \"{}\" provided",
+                    process.name,
+                    dependency
+                );
+            }
+
+            let owner = format!("Process \"{}\"", process.name);
+
+            for (_, onexit) in &process.onexit {
+                Self::check_actions(&owner, "onexit", onexit, &process_map)?;
+            }
+
+            Self::check_actions(&owner, "oncrashloop", &process.oncrashloop, &process_map)?;
+            Self::check_actions(&owner, "onfail", &process.onfail, &process_map)?;
+            Self::check_actions(&owner, "onsucceed", &process.onsucceed, &process_map)?;
+            Self::check_actions(&owner, "ontimeout", &process.ontimeout, &process_map)?;
+        }
+
+        Self::check_dependency_cycles(&process_map)?;
+
+        Ok(process_map)
+    }
+
+    /// Ensures that each of the specified actions of the owning process or log monitor is a builtin
+    /// action or a defined process.
+    fn check_actions(
+        owner: &str,
+        hook: &str,
+        actions: &[String],
+        process_map: &HashMap<String, Process>,
+    ) -> Result<()> {
+        for action in actions {
+            let valid = match action.parse::<Builtin>() {
+                Ok(Builtin::Signal { process, .. }) => process_map.contains_key(&process),
+                Ok(_) => true,
+                Err(_) => process_map.contains_key(action),
+            };
+
+            ensure!(valid, "{}: invalid {} \"{}\" provided", owner, hook, action);
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that no process depends on itself, directly or through other processes.
+    fn check_dependency_cycles(process_map: &HashMap<String, Process>) -> Result<()> {
+        fn visit<'a>(
+            name: &'a str,
+            process_map: &'a HashMap<String, Process>,
+            path: &mut Vec<&'a str>,
+            visited: &mut HashSet<&'a str>,
+        ) -> Result<()> {
+            if let Some(start) = path.iter().position(|n| *n == name) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(name);
+
+                bail!(
+                    "Process \"{}\": dependency cycle {} detected",
+                    name,
+                    cycle
+                        .iter()
+                        .map(|n| format!("\"{}\"", n))
+                        .collect::<Vec<String>>()
+                        .join(" -> ")
+                );
+            }
+
+            if !visited.insert(name) {
+                return Ok(());
+            }
+
+            path.push(name);
+
+            for dependency in &process_map[name].depends_on {
+                visit(dependency, process_map, path, visited)?;
+            }
+
+            path.pop();
+
+            Ok(())
+        }
+
+        let mut names = process_map.keys().collect::<Vec<&String>>();
+        names.sort();
+
+        let mut visited = HashSet::new();
+
+        for name in names {
+            visit(name, process_map, &mut Vec::new(), &mut visited)?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds a list of actions, given as a single action or a list of them.
+    fn build_actions(actions: deserialize::actions::Actions) -> Vec<String> {
+        match actions {
+            deserialize::actions::Actions::One(action) if action.is_empty() => Vec::new(),
+            deserialize::actions::Actions::One(action) => vec![action],
+            deserialize::actions::Actions::Many(actions) => actions,
+        }
+    }
+
+    /// Parses the keys of an `onexit` map into exit conditions.
+    fn build_onexit(
+        onexit: HashMap<deserialize::processes::ExitKey, deserialize::actions::Actions>,
+    ) -> Result<Vec<(ExitCondition, Vec<String>)>> {
+        let mut onexit = onexit
+            .into_iter()
+            .map(|(key, action)| {
+                let (key, condition) = match key {
+                    deserialize::processes::ExitKey::Code(code) => {
+                        (code.to_string(), ExitCondition::Code(code))
+                    }
+                    deserialize::processes::ExitKey::Condition(condition) => {
+                        let parsed = condition.parse()?;
+
+                        (condition, parsed)
+                    }
+                };
+
+                Ok((key, condition, Self::build_actions(action)))
+            })
+            .collect::<Result<Vec<(String, ExitCondition, Vec<String>)>, Error>>()?;
+
+        onexit.sort_by(|(a_key, a, _), (b_key, b, _)| a.cmp(b).then_with(|| a_key.cmp(b_key)));
+
+        for pair in onexit.windows(2) {
+            ensure!(
+                pair[0].1 != pair[1].1,
+                "Duplicate onexit conditions \"{}\" and \"{}\"",
+                pair[0].0,
+                pair[1].0
+            );
+        }
+
+        Ok(onexit
+            .into_iter()
+            .map(|(_, condition, actions)| (condition, actions))
+            .collect())
+    }
+
+    /// Builds a capture into `var`, ensuring that `regex` is provided only when capturing from a
+    /// regular expression.
+    fn build_capture(capture: deserialize::processes::Capture) -> Result<Capture> {
+        ensure!(
+            Regex::new("^[A-Za-z_][A-Za-z0-9_]*$")?.is_match(&capture.var),
+            "Expected var to be a variable name such as IMAGE_TAG, got \"{}\"",
+            capture.var
+        );
+
+        let mode = match (capture.from, capture.regex) {
+            (deserialize::processes::CaptureFrom::Stdout, None) => CaptureMode::Stdout,
+            (deserialize::processes::CaptureFrom::LastLine, None) => CaptureMode::LastLine,
+            (deserialize::processes::CaptureFrom::Regex, Some(regex)) => {
+                Regex::new(&regex).context(format!("Invalid regex \"{}\"", regex))?;
+
+                CaptureMode::Regex(regex)
+            }
+            (deserialize::processes::CaptureFrom::Regex, None) => {
+                bail!("Expected regex to be provided when capturing from regex")
+            }
+            (_, Some(_)) => bail!("Expected regex to only be provided when capturing from regex"),
+        };
+
+        Ok(Capture::new(capture.var, mode))
+    }
+
+    /// Builds resource limits, parsing sizes given with a `K`, `M`, or `G` suffix.
+    fn build_limits(limits: deserialize::processes::Limits) -> Result<Limits> {
+        Ok(Limits::new()
+            .address_space(
+                limits
+                    .address_space
+                    .map(Self::build_byte_size)
+                    .transpose()
+                    .context("Invalid address_space")?,
+            )
+            .core_size(
+                limits
+                    .core_size
+                    .map(Self::build_byte_size)
+                    .transpose()
+                    .context("Invalid core_size")?,
+            )
+            .cpu(limits.cpu)
+            .open_files(limits.open_files)
+            .processes(limits.processes))
+    }
+
+    /// Converts a size given in bytes, or with a `K`, `M`, or `G` suffix, into bytes.
+    fn build_byte_size(size: deserialize::processes::ByteSize) -> Result<u64> {
+        let text = match size {
+            deserialize::processes::ByteSize::Bytes(bytes) => return Ok(bytes),
+            deserialize::processes::ByteSize::Text(text) => text,
+        };
+        let trimmed = text.trim();
+        let (digits, multiplier) = match trimmed.char_indices().last() {
+            Some((i, 'K' | 'k')) => (&trimmed[..i], 1 << 10),
+            Some((i, 'M' | 'm')) => (&trimmed[..i], 1 << 20),
+            Some((i, 'G' | 'g')) => (&trimmed[..i], 1 << 30),
+            _ => (trimmed, 1),
+        };
+
+        digits
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .and_then(|value| value.checked_mul(multiplier))
+            .context(format!(
+                "Expected a number of bytes, optionally followed by K, M, or G, got \"{}\"",
+                text
+            ))
+    }
+
+    fn build_max_line_length(size: deserialize::processes::ByteSize) -> Result<usize> {
+        let bytes = Self::build_byte_size(size)?;
+
+        ensure!(bytes > 0, "Expected a positive number of bytes");
+
+        usize::try_from(bytes).context(format!("Expected at most {} bytes", usize::MAX))
+    }
+
+    /// Builds a readiness probe from exactly one of its `command`, `file`, `log`, or `port` checks.
+    fn build_readiness(ready_when: deserialize::processes::ReadyWhen) -> Result<Readiness> {
+        let deserialize::processes::ReadyWhen {
+            command,
+            file,
+            host,
+            interval,
+            log,
+            port,
+            timeout,
+        } = ready_when;
+
+        ensure!(
+            host.is_none() || port.is_some(),
+            "Expected host to be provided with port"
+        );
+
+        let probe = match (command, file, log, port) {
+            (Some(command), None, None, None) => Probe::Command(command),
+            (None, Some(file), None, None) => Probe::File(file),
+            (None, None, Some(log), None) => {
+                Regex::new(&log).context(format!("Invalid log pattern \"{}\"", log))?;
+
+                Probe::Log(log)
+            }
+            (None, None, None, Some(port)) => {
+                Probe::Port(host.unwrap_or_else(|| "localhost".to_owned()), port)
+            }
+            _ => bail!("Expected exactly one of command, file, log, or port"),
+        };
+
+        let mut readiness = Readiness::new(probe)
+            .timeout(Self::build_duration(timeout).context("Invalid ready_when timeout")?);
+
+        if let Some(interval) =
+            Self::build_duration(interval).context("Invalid ready_when interval")?
+        {
+            ensure!(
+                !interval.is_zero(),
+                "Expected interval to be greater than zero"
+            );
+
+            readiness = readiness.interval(interval);
+        }
+
+        Ok(readiness)
+    }
+
+    /// Builds a `BinCommand` overriding the runtime one, if `bin` is provided.
+    fn build_bin_command(bin: String, args: Vec<String>) -> Result<Option<BinCommand>> {
+        match &bin[..] {
+            "" => {
+                ensure!(args.is_empty(), "Expected bin to be provided with args");
+
+                Ok(None)
+            }
+            _ => Ok(Some(BinCommand::new(bin, args))),
+        }
+    }
+
+    /// Converts a number of seconds, if any, into a `Duration`.
+    fn build_duration(secs: Option<f64>) -> Result<Option<Duration>> {
+        secs.map(|secs| {
+            Duration::try_from_secs_f64(secs).context(format!(
+                "Expected a non-negative number of seconds, got {}",
+                secs
+            ))
+        })
+        .transpose()
+    }
+
+    /// Merges variables loaded from `env_file`, if any, with `env`. Variables in `env` take
+    /// precedence.
+    fn build_env(env_file: &str, env: HashMap<String, String>) -> Result<HashMap<String, String>> {
+        let mut merged = match env_file {
+            "" => HashMap::new(),
+            path => env_file::load(path)?,
+        };
+
+        merged.extend(env);
+
+        Ok(merged)
     }
 }
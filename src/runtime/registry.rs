@@ -0,0 +1,73 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Index of running process children, shared across all threads of a runtime.
+///
+/// Each child is spawned as the leader of its own process group, so the pids stored here also
+/// identify the process groups which should be signaled when the runtime shuts down.
+///
+/// A [`child`] registry indexes only the children recorded through it, such as those of a single
+/// task, while also recording them in its parent.
+///
+/// [`child`]: #method.child
+#[derive(Clone, Debug, Default)]
+pub struct ProcessRegistry {
+    parent: Option<Box<ProcessRegistry>>,
+    processes: Arc<Mutex<HashMap<u32, String>>>,
+}
+
+impl ProcessRegistry {
+    /// Constructs a new, empty `ProcessRegistry`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new, empty child `ProcessRegistry`, whose children are also recorded in this
+    /// one.
+    pub fn child(&self) -> Self {
+        Self {
+            parent: Some(Box::new(self.clone())),
+            ..Self::default()
+        }
+    }
+
+    /// Records a running child with the specified pid and process name.
+    pub fn insert(&self, pid: u32, name: String) {
+        if let Some(parent) = &self.parent {
+            parent.insert(pid, name.clone());
+        }
+
+        self.lock().insert(pid, name);
+    }
+
+    /// Removes the child with the specified pid.
+    pub fn remove(&self, pid: u32) {
+        if let Some(parent) = &self.parent {
+            parent.remove(pid);
+        }
+
+        self.lock().remove(&pid);
+    }
+
+    /// Returns the pids of all running children.
+    pub fn pids(&self) -> Vec<u32> {
+        self.lock().keys().copied().collect()
+    }
+
+    /// Returns the pids of all running children of the process with the specified name.
+    pub fn pids_of(&self, name: &str) -> Vec<u32> {
+        self.lock()
+            .iter()
+            .filter(|(_, process)| *process == name)
+            .map(|(pid, _)| *pid)
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<u32, String>> {
+        self.processes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    collections::HashMap,
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Position within the runtime from which a process or log monitor `test` is executed.
+///
+/// The scope is exposed to each executed command as the `ARPX_RUN_ID`, `ARPX_JOB`,
+/// `ARPX_TASK_INDEX`, `ARPX_PROCESS`, and `ARPX_PROFILE_DIR` environment variables, along with
+/// those describing the parent of an action, if any. Tasks are numbered from 1, as they are in
+/// error messages.
+#[derive(Clone, Debug, Default)]
+pub struct Scope {
+    pub job: String,
+    pub parent: Option<Parent>,
+    pub profile_dir: Option<String>,
+    pub run_id: String,
+    pub task_index: usize,
+}
+
+/// Process whose exit or log monitor trigger caused an action to be executed.
+///
+/// This is exposed to the action as `ARPX_PARENT_PROCESS`, along with `ARPX_PARENT_EXIT_CODE` if
+/// the parent exited with an exit code, and `ARPX_TRIGGER_BUFFER` if the action was triggered by
+/// a log monitor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Parent {
+    pub exit_code: Option<i32>,
+    pub process: String,
+    pub trigger_buffer: Option<String>,
+}
+
+impl Scope {
+    /// Constructs a new `Scope` with an identifier unique to a single run of the runtime.
+    pub fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        Self {
+            run_id: format!("{:x}-{:x}", nanos, process::id()),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the scope as environment variables for a command executed on behalf of the
+    /// specified process.
+    pub fn env(&self, process: &str) -> HashMap<String, String> {
+        let mut env = HashMap::from([
+            ("ARPX_RUN_ID".to_owned(), self.run_id.clone()),
+            ("ARPX_JOB".to_owned(), self.job.clone()),
+            ("ARPX_TASK_INDEX".to_owned(), self.task_index.to_string()),
+            ("ARPX_PROCESS".to_owned(), process.to_owned()),
+        ]);
+
+        if let Some(profile_dir) = &self.profile_dir {
+            env.insert("ARPX_PROFILE_DIR".to_owned(), profile_dir.clone());
+        }
+
+        if let Some(parent) = &self.parent {
+            env.insert("ARPX_PARENT_PROCESS".to_owned(), parent.process.clone());
+
+            if let Some(exit_code) = parent.exit_code {
+                env.insert("ARPX_PARENT_EXIT_CODE".to_owned(), exit_code.to_string());
+            }
+
+            if let Some(trigger_buffer) = &parent.trigger_buffer {
+                env.insert("ARPX_TRIGGER_BUFFER".to_owned(), trigger_buffer.clone());
+            }
+        }
+
+        env
+    }
+}
@@ -0,0 +1,179 @@
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender};
+use std::{
+    fmt,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+    time::Duration,
+};
+
+/// Token used to coordinate the shutdown of a runtime across all of its threads.
+///
+/// Once triggered, the token records the status with which the runtime should exit and,
+/// optionally, the signal which should be delivered to running processes. Threads can observe the
+/// token by polling it or by selecting on its [`receiver`], which disconnects when the token is
+/// triggered.
+///
+/// A [`child`] token can be triggered on its own, such as to stop the processes of a single task,
+/// and is also triggered along with its parent.
+///
+/// [`receiver`]: #method.receiver
+/// [`child`]: #method.child
+#[derive(Clone, Debug)]
+pub struct Shutdown {
+    receiver: Receiver<()>,
+    root: Option<Box<Shutdown>>,
+    state: Arc<Mutex<ShutdownState>>,
+}
+
+#[derive(Debug)]
+struct ShutdownState {
+    children: Vec<Weak<Mutex<ShutdownState>>>,
+    code: Option<i32>,
+    sender: Option<Sender<()>>,
+    signal: Option<i32>,
+}
+
+impl ShutdownState {
+    fn trigger(&mut self, code: i32, signal: Option<i32>) {
+        self.code = Some(code);
+        self.signal = signal;
+        self.sender.take();
+
+        for child in self.children.drain(..).filter_map(|child| child.upgrade()) {
+            let mut child = child
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            if child.code.is_none() {
+                child.trigger(code, signal);
+            }
+        }
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    /// Constructs a new, untriggered `Shutdown`.
+    pub fn new() -> Self {
+        let (sender, receiver) = bounded(0);
+
+        Self {
+            receiver,
+            root: None,
+            state: Arc::new(Mutex::new(ShutdownState {
+                children: Vec::new(),
+                code: None,
+                sender: Some(sender),
+                signal: None,
+            })),
+        }
+    }
+
+    /// Constructs a new child `Shutdown`, which is triggered along with this one.
+    ///
+    /// If this token has already been triggered, the child is triggered with the same exit status
+    /// and signal.
+    pub fn child(&self) -> Self {
+        let child = Self {
+            root: Some(Box::new(self.root().clone())),
+            ..Self::new()
+        };
+        let mut state = self.lock();
+
+        match state.code {
+            Some(code) => child.lock().trigger(code, state.signal),
+            None => {
+                state.children.retain(|child| child.strong_count() > 0);
+                state.children.push(Arc::downgrade(&child.state));
+            }
+        }
+
+        child
+    }
+
+    /// Returns the token from which this token, and any of its ancestors, were created.
+    ///
+    /// Triggering the root token shuts down the runtime.
+    pub fn root(&self) -> &Self {
+        self.root.as_deref().unwrap_or(self)
+    }
+
+    /// Triggers shutdown with the specified exit status.
+    ///
+    /// Only the first trigger takes effect. Returns whether this call triggered shutdown.
+    pub fn trigger(&self, code: i32) -> bool {
+        self.trigger_with_signal(code, None)
+    }
+
+    /// Triggers shutdown with the specified exit status and the signal which should be delivered
+    /// to running processes.
+    ///
+    /// Only the first trigger takes effect. Returns whether this call triggered shutdown.
+    pub fn trigger_with_signal(&self, code: i32, signal: Option<i32>) -> bool {
+        let mut state = self.lock();
+
+        if state.code.is_some() {
+            return false;
+        }
+
+        state.trigger(code, signal);
+
+        true
+    }
+
+    /// Returns whether shutdown has been triggered.
+    pub fn is_triggered(&self) -> bool {
+        self.lock().code.is_some()
+    }
+
+    /// Returns the exit status with which shutdown was triggered, if any.
+    pub fn code(&self) -> Option<i32> {
+        self.lock().code
+    }
+
+    /// Returns the signal which should be delivered to running processes, if any.
+    pub fn signal(&self) -> Option<i32> {
+        self.lock().signal
+    }
+
+    /// Returns a receiver which disconnects once shutdown is triggered.
+    ///
+    /// No messages are ever sent on this receiver.
+    pub fn receiver(&self) -> &Receiver<()> {
+        &self.receiver
+    }
+
+    /// Blocks for the specified duration or until shutdown is triggered, whichever is sooner.
+    ///
+    /// Returns whether shutdown has been triggered.
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        !matches!(
+            self.receiver.recv_timeout(timeout),
+            Err(RecvTimeoutError::Timeout)
+        )
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ShutdownState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Error returned from a runtime which was shut down with a non-zero exit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitError {
+    pub code: i32,
+}
+
+impl fmt::Display for ExitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Runtime exited with status {}", self.code)
+    }
+}
+
+impl std::error::Error for ExitError {}
@@ -0,0 +1,221 @@
+use crate::runtime::{registry::ProcessRegistry, shutdown::Shutdown};
+use anyhow::Result;
+#[cfg(unix)]
+use anyhow::{Context, Error};
+#[cfg(unix)]
+use log::info;
+#[cfg(unix)]
+use signal_hook::{consts::TERM_SIGNALS, iterator::Handle, iterator::Signals};
+#[cfg(unix)]
+use std::{io, mem, ptr, sync::Mutex, thread};
+
+#[cfg(unix)]
+pub const SIGKILL: i32 = libc::SIGKILL;
+#[cfg(not(unix))]
+pub const SIGKILL: i32 = 9;
+
+#[cfg(unix)]
+pub const SIGTERM: i32 = libc::SIGTERM;
+#[cfg(not(unix))]
+pub const SIGTERM: i32 = 15;
+
+#[cfg(unix)]
+pub const SIGXCPU: i32 = libc::SIGXCPU;
+#[cfg(not(unix))]
+pub const SIGXCPU: i32 = 24;
+
+/// Signals which can be referenced by name in profiles, paired with their names.
+#[cfg(unix)]
+const SIGNALS: [(libc::c_int, &str); 28] = [
+    (libc::SIGHUP, "SIGHUP"),
+    (libc::SIGINT, "SIGINT"),
+    (libc::SIGQUIT, "SIGQUIT"),
+    (libc::SIGILL, "SIGILL"),
+    (libc::SIGTRAP, "SIGTRAP"),
+    (libc::SIGABRT, "SIGABRT"),
+    (libc::SIGBUS, "SIGBUS"),
+    (libc::SIGFPE, "SIGFPE"),
+    (libc::SIGKILL, "SIGKILL"),
+    (libc::SIGUSR1, "SIGUSR1"),
+    (libc::SIGSEGV, "SIGSEGV"),
+    (libc::SIGUSR2, "SIGUSR2"),
+    (libc::SIGPIPE, "SIGPIPE"),
+    (libc::SIGALRM, "SIGALRM"),
+    (libc::SIGTERM, "SIGTERM"),
+    (libc::SIGCHLD, "SIGCHLD"),
+    (libc::SIGCONT, "SIGCONT"),
+    (libc::SIGSTOP, "SIGSTOP"),
+    (libc::SIGTSTP, "SIGTSTP"),
+    (libc::SIGTTIN, "SIGTTIN"),
+    (libc::SIGTTOU, "SIGTTOU"),
+    (libc::SIGURG, "SIGURG"),
+    (libc::SIGXCPU, "SIGXCPU"),
+    (libc::SIGXFSZ, "SIGXFSZ"),
+    (libc::SIGVTALRM, "SIGVTALRM"),
+    (libc::SIGPROF, "SIGPROF"),
+    (libc::SIGWINCH, "SIGWINCH"),
+    (libc::SIGSYS, "SIGSYS"),
+];
+
+/// Returns the number of the signal with the specified name.
+///
+/// Names are matched case-insensitively, with or without the `SIG` prefix (e.g. `SIGTERM`,
+/// `TERM`, and `term` are equivalent).
+#[cfg(unix)]
+pub fn from_name(name: &str) -> Option<i32> {
+    let name = name.to_uppercase();
+    let name = name.strip_prefix("SIG").unwrap_or(&name);
+
+    SIGNALS
+        .iter()
+        .find(|(_, signal_name)| signal_name[3..] == *name)
+        .map(|(signal, _)| *signal)
+}
+
+#[cfg(not(unix))]
+pub fn from_name(_name: &str) -> Option<i32> {
+    None
+}
+
+/// Returns the name of the specified signal number, if known.
+#[cfg(unix)]
+pub fn name(signal: i32) -> Option<&'static str> {
+    SIGNALS
+        .iter()
+        .find(|(number, _)| *number == signal)
+        .map(|(_, name)| *name)
+}
+
+#[cfg(not(unix))]
+pub fn name(_signal: i32) -> Option<&'static str> {
+    None
+}
+
+/// Sends the specified signal to the process group led by the specified pid.
+#[cfg(unix)]
+pub fn send_to_group(pid: u32, signal: i32) -> io::Result<()> {
+    match unsafe { libc::killpg(pid as libc::pid_t, signal) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn send_to_group(_pid: u32, _signal: i32) -> std::io::Result<()> {
+    Err(std::io::ErrorKind::Unsupported.into())
+}
+
+/// Dispositions which route termination signals to the handlers, saved when first installed so
+/// that they can be put back each time the handlers are installed again.
+#[cfg(unix)]
+static INSTALLED_ACTIONS: Mutex<Vec<(i32, libc::sigaction)>> = Mutex::new(Vec::new());
+
+/// Handlers for signals which terminate the runtime.
+///
+/// Once installed, a received termination signal (`SIGINT`, `SIGTERM`, or `SIGQUIT`) triggers
+/// shutdown of the runtime with the conventional exit status for that signal (128 plus the signal
+/// number), and running processes are stopped by forwarding the same signal to them. Upon
+/// receiving a second termination signal, all running processes are killed immediately.
+///
+/// The dispositions of the termination signals from before the handlers were installed are
+/// restored once they are closed, so that a program embedding the runtime behaves as before.
+pub struct SignalHandlers {
+    #[cfg(unix)]
+    handle: Handle,
+    #[cfg(unix)]
+    previous_actions: Vec<(i32, libc::sigaction)>,
+}
+
+impl SignalHandlers {
+    /// Installs signal handlers which act upon the specified shutdown token and registry.
+    #[cfg(unix)]
+    pub fn install(shutdown: Shutdown, registry: ProcessRegistry) -> Result<Self> {
+        let previous_actions = TERM_SIGNALS
+            .iter()
+            .map(|signal| Ok((*signal, get_action(*signal)?)))
+            .collect::<io::Result<Vec<(i32, libc::sigaction)>>>()
+            .context("Error reading signal dispositions")?;
+        let mut signals = Signals::new(TERM_SIGNALS).context("Error installing signal handlers")?;
+        let handle = signals.handle();
+
+        // Signal handlers are only registered with the operating system once per program, so any
+        // dispositions restored by an earlier `close` are replaced by the saved handlers.
+        let mut installed_actions = INSTALLED_ACTIONS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match installed_actions.is_empty() {
+            true => {
+                for signal in TERM_SIGNALS {
+                    installed_actions.push((*signal, get_action(*signal)?));
+                }
+            }
+            false => {
+                for (signal, action) in installed_actions.iter() {
+                    set_action(*signal, action).context("Error installing signal handlers")?;
+                }
+            }
+        }
+
+        thread::Builder::new()
+            .name("arpx".to_owned())
+            .spawn(move || {
+                for signal in signals.forever() {
+                    let signal_name = name(signal).unwrap_or("signal");
+
+                    if shutdown.trigger_with_signal(128 + signal, Some(signal)) {
+                        info!("Received {}, stopping processes", signal_name);
+                    } else {
+                        info!("Received {}, killing processes", signal_name);
+
+                        for pid in registry.pids() {
+                            send_to_group(pid, SIGKILL).ok();
+                        }
+                    }
+                }
+            })
+            .map_err(Error::new)?;
+
+        Ok(Self {
+            handle,
+            previous_actions,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn install(_shutdown: Shutdown, _registry: ProcessRegistry) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    /// Uninstalls the signal handlers and restores the previous signal dispositions.
+    pub fn close(self) {
+        #[cfg(unix)]
+        {
+            self.handle.close();
+
+            for (signal, action) in &self.previous_actions {
+                set_action(*signal, action).ok();
+            }
+        }
+    }
+}
+
+/// Returns the current disposition of the specified signal.
+#[cfg(unix)]
+fn get_action(signal: i32) -> io::Result<libc::sigaction> {
+    let mut action: libc::sigaction = unsafe { mem::zeroed() };
+
+    match unsafe { libc::sigaction(signal, ptr::null(), &mut action) } {
+        0 => Ok(action),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+/// Sets the disposition of the specified signal.
+#[cfg(unix)]
+fn set_action(signal: i32, action: &libc::sigaction) -> io::Result<()> {
+    match unsafe { libc::sigaction(signal, action, ptr::null_mut()) } {
+        0 => Ok(()),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
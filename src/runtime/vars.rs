@@ -0,0 +1,39 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Variables captured from process output, shared across all threads of a runtime.
+///
+/// Each variable is exposed to subsequently executed processes and log monitor tests as an
+/// `ARPX_VAR_<NAME>` environment variable.
+#[derive(Clone, Debug, Default)]
+pub struct Vars {
+    vars: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Vars {
+    /// Constructs a new, empty `Vars`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the variable with the specified name, replacing any previous value.
+    pub fn set(&self, name: String, value: String) {
+        self.lock().insert(name, value);
+    }
+
+    /// Returns all variables as environment variables.
+    pub fn env(&self) -> HashMap<String, String> {
+        self.lock()
+            .iter()
+            .map(|(name, value)| (format!("ARPX_VAR_{}", name), value.clone()))
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        self.vars
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -3,7 +3,7 @@ macro_rules! test {
     ($name:ident, $fn:expr) => {
         #[test]
         fn $name() {
-            let test_object = crate::common::TC::new(stringify!($name));
+            let test_object = $crate::common::TC::new(stringify!($name));
 
             $fn(test_object);
         }
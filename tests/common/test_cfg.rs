@@ -5,8 +5,9 @@ use std::{
     env::temp_dir,
     fs::{write, File},
     io::{BufRead, BufReader},
-    process::{Command, Stdio},
-    time::{SystemTime, UNIX_EPOCH},
+    process::{Command, ExitStatus, Stdio},
+    thread::sleep,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 pub struct TC {
@@ -16,6 +17,7 @@ pub struct TC {
     pub name: String,
     pub opts: String,
     pub profile: String,
+    pub signal: Option<(String, f64)>,
 }
 
 impl TC {
@@ -27,6 +29,7 @@ impl TC {
             opts: String::new(),
             profile: String::new(),
             profile_path: String::new(),
+            signal: None,
         }
     }
 
@@ -37,6 +40,15 @@ impl TC {
         self
     }
 
+    #[allow(dead_code)]
+    pub fn file(&self, name: &str, contents: &str) -> String {
+        let mut path = temp_dir();
+        path.push(format!("{}_{}_{}", self.name, self.datetime, name));
+        write(&path, contents).unwrap();
+
+        path.as_path().display().to_string()
+    }
+
     pub fn profile(mut self, input: &str) -> Self {
         self.profile.push_str(input);
 
@@ -58,25 +70,48 @@ impl TC {
         self
     }
 
+    #[allow(dead_code)]
+    pub fn signal(mut self, signal: &str, after_secs: f64) -> Self {
+        self.signal = Some((signal.to_owned(), after_secs));
+
+        self
+    }
+
     pub fn run(self) -> Result<(Vec<String>, Vec<String>)> {
+        self.run_with_status().map(|(out, err, _)| (out, err))
+    }
+
+    pub fn run_with_status(self) -> Result<(Vec<String>, Vec<String>, ExitStatus)> {
         let BinCommand { bin, mut args } = BinCommand::system_default();
 
         let test_command = format!(
-            "{} -f {} {}",
+            "exec {} -f {} {}",
             env!("CARGO_BIN_EXE_arpx"),
             self.profile_path,
             self.opts
         );
         args.push(test_command);
 
-        let output = Command::new(bin)
+        let child = Command::new(bin)
             .args(args)
             .envs(self.envs)
             .current_dir(String::from(env!("CARGO_MANIFEST_DIR")))
+            .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
-            .context("Failed to spawn test command")?
+            .context("Failed to spawn test command")?;
+
+        if let Some((signal, after_secs)) = self.signal {
+            sleep(Duration::from_secs_f64(after_secs));
+
+            Command::new("kill")
+                .args(["-s", &signal[..], &child.id().to_string()[..]])
+                .status()
+                .context("Failed to signal test command")?;
+        }
+
+        let output = child
             .wait_with_output()
             .context("Failed to wait test command")?;
 
@@ -89,7 +124,7 @@ impl TC {
             .map(|l| l.expect("!parse"))
             .collect();
 
-        Ok((out, err))
+        Ok((out, err, output.status))
     }
 
     fn datetime() -> String {
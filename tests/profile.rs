@@ -5,8 +5,6 @@ use common::TC;
 // TODO:
 // - profile file doesn't exist
 // - invalid job/process/log_monitor name syntax (alphanumeric + - + _)
-// - invalid process (onfail/onsucceed doesn't exist, etc.)
-// - invalid log_monitor (ontrigger doesn't exist, etc.)
 
 /*
  * jobs
@@ -14,24 +12,18 @@ use common::TC;
 test!(invalid_jobs_yaml, |t: TC| {
     let (out, err) = t.profile("jobs: oops").opts("-j test").run().unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error deserializing file"));
-    assert_eq!(
-        true,
-        err[4].contains("jobs: invalid type: string \"oops\", expected a map")
-    );
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error deserializing file"));
+    assert!(err[4].contains("jobs: invalid type: string \"oops\", expected a map"));
     assert_eq!(0, out.len());
 });
 
 test!(invalid_jobs_yaml_empty, |t: TC| {
     let (out, err) = t.profile("jobs:").opts("-j test").run().unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error deserializing file"));
-    assert_eq!(
-        true,
-        err[4].contains("jobs: invalid type: unit value, expected a map")
-    );
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error deserializing file"));
+    assert!(err[4].contains("jobs: invalid type: unit value, expected a map"));
     assert_eq!(0, out.len());
 });
 
@@ -47,9 +39,9 @@ test!(job_parse_error, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error deserializing file"));
-    assert_eq!(true, err[4].contains("Parse error at job line 1 column 0"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error deserializing file"));
+    assert!(err[4].contains("Parse error at job line 1 column 0"));
     assert_eq!(0, out.len());
 });
 
@@ -69,8 +61,8 @@ test!(job_not_defined, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(
         err[4],
         "    1: Requested job \"does_not_exist\" not defined in jobs"
@@ -94,8 +86,8 @@ test!(job_uses_nonexistent_process, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(
         err[4],
         "    1: Job \"test\", task 1: process \"does_not_exist\" not defined in processes"
@@ -119,8 +111,8 @@ test!(job_uses_nonexistent_log_monitor, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(
         err[4],
         "    1: Job \"test\", task 1: log monitor \"does_not_exist\" not defined in log_monitors"
@@ -134,12 +126,9 @@ test!(job_uses_nonexistent_log_monitor, |t: TC| {
 test!(invalid_processes_yaml, |t: TC| {
     let (out, err) = t.profile("processes: oops").opts("-j test").run().unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error deserializing file"));
-    assert_eq!(
-        true,
-        err[4].contains("processes: invalid type: string \"oops\", expected a map")
-    );
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error deserializing file"));
+    assert!(err[4].contains("processes: invalid type: string \"oops\", expected a map"));
     assert_eq!(0, out.len());
 });
 
@@ -155,8 +144,8 @@ test!(no_processes_defined, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(err[4], "    1: No valid processes exist in profile");
     assert_eq!(0, out.len());
 });
@@ -178,8 +167,8 @@ test!(processes_max, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(err[4], "    1: Too many processes defined in profile");
     assert_eq!(0, out.len());
 });
@@ -207,8 +196,8 @@ test!(concurrent_processes_max, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(err[4], "    1: Job \"test\", task 1: too many processes");
     assert_eq!(0, out.len());
 });
@@ -223,12 +212,9 @@ test!(invalid_log_monitors_yaml, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error deserializing file"));
-    assert_eq!(
-        true,
-        err[4].contains("log_monitors: invalid type: string \"oops\", expected a map")
-    );
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error deserializing file"));
+    assert!(err[4].contains("log_monitors: invalid type: string \"oops\", expected a map"));
     assert_eq!(0, out.len());
 });
 
@@ -254,8 +240,8 @@ test!(log_monitors_max, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(err[4], "    1: Too many log_monitors defined in profile");
     assert_eq!(0, out.len());
 });
@@ -280,11 +266,614 @@ test!(thread_max, |t: TC| {
         .run()
         .unwrap();
 
-    assert_eq!(true, err[0].contains("Error loading profile"));
-    assert_eq!(true, err[3].contains("Error building runtime"));
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
     assert_eq!(
         err[4],
         "    1: Job \"test\", task 1: too many threads (reduce processes or log_monitors on task)"
     );
     assert_eq!(0, out.len());
 });
+
+test!(invalid_env_file, |t: TC| {
+    let env_file = t.file("test.env", "FOO=foo\nBAR\n");
+    let (out, err) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    env_file: {}
+         "#,
+            env_file
+        ))
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid env on process \"foo\"");
+    assert!(err[5].contains("line 2: expected KEY=VALUE"));
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_ontimeout, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    timeout: 1
+                    ontimeout: does_not_exist
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"foo\": invalid ontimeout \"does_not_exist\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_oncrashloop, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    restart: on-failure
+                    max_restarts: 3
+                    oncrashloop: does_not_exist
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"foo\": invalid oncrashloop \"does_not_exist\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_onexit, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    onexit:
+                        SIGNOPE: foo
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid onexit on process \"foo\"");
+    assert!(err[5].contains("Invalid exit condition \"SIGNOPE\""));
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_onexit_action, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    onexit:
+                        2: does_not_exist
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"foo\": invalid onexit \"does_not_exist\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(duplicate_onexit_condition, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    onexit:
+                        SIGTERM: bar
+                        term: bar
+                bar:
+                    command: echo bar
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid onexit on process \"foo\"");
+    assert_eq!(
+        err[5],
+        "    2: Duplicate onexit conditions \"SIGTERM\" and \"term\""
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(onexit_ranges_of_equal_width, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: exit 3
+                    onexit:
+                        3-5: baz
+                        2-4: bar
+                bar:
+                    command: echo bar
+                baz:
+                    command: echo baz
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[foo] bar", out[3]);
+    assert_eq!(5, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(invalid_onfail_in_list, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: exit 1
+                    onfail: [bar, does_not_exist]
+                bar:
+                    command: echo bar
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"foo\": invalid onfail \"does_not_exist\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_exit_status, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: exit 1
+                    onfail: arpx_exit(256)
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"foo\": invalid onfail \"arpx_exit(256)\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_signal_action, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: exit 1
+                    onfail: arpx_signal:does_not_exist:HUP
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"foo\": invalid onfail \"arpx_signal:does_not_exist:HUP\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_ontrigger, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo; @bar
+
+            processes:
+                foo:
+                    command: echo foo
+
+            log_monitors:
+                bar:
+                    ontrigger: does_not_exist
+                    test: 'echo "$ARPX_BUFFER" | grep -q "foo"'
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Log monitor \"bar\": invalid ontrigger \"does_not_exist\" provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_stop_signal, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    stop_signal: SIGNOPE
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Invalid stop_signal \"SIGNOPE\" on process \"foo\""
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_stdin, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: cat
+                    stdin: inherit
+                    tty: true
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Configured stdin on process \"foo\" cannot be used with tty"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_ready_when, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    ready_when:
+                        file: foo.txt
+                        port: 8080
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid ready_when on process \"foo\"");
+    assert_eq!(
+        err[5],
+        "    2: Expected exactly one of command, file, log, or port"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(dependency_cycle, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    depends_on: [bar]
+                bar:
+                    command: echo bar
+                    depends_on: [baz]
+                baz:
+                    command: echo baz
+                    depends_on: [bar]
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Process \"bar\": dependency cycle \"bar\" -> \"baz\" -> \"bar\" detected"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(dependency_not_scheduled, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    api;
+                    db;
+
+            processes:
+                api:
+                    command: echo api
+                    depends_on: [db]
+                db:
+                    command: echo db
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Job \"test\", task 1: process \"api\" depends on \"db\", which does not run in the same task or an earlier one"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_limits, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    limits:
+                        address_space: 512X
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid limits on process \"foo\"");
+    assert_eq!(err[5], "    2: Invalid address_space");
+    assert_eq!(
+        err[6],
+        "    3: Expected a number of bytes, optionally followed by K, M, or G, got \"512X\""
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_user, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    user: arpx_user_does_not_exist
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid user on process \"foo\"");
+    assert_eq!(err[5], "    2: Unknown user \"arpx_user_does_not_exist\"");
+    assert_eq!(0, out.len());
+});
+
+test!(user_without_group, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    user: 3999999
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Missing group on process \"foo\": user \"3999999\" has no primary group, so group must be provided"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_bin, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    args: ["-c"]
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid bin on process \"foo\"");
+    assert_eq!(err[5], "    2: Expected bin to be provided with args");
+    assert_eq!(0, out.len());
+});
+
+test!(empty_argv_command, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: []
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(
+        err[4],
+        "    1: Configured command on process \"foo\" must not be an empty list"
+    );
+    assert_eq!(0, out.len());
+});
+
+test!(invalid_capture, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: foo;
+
+            processes:
+                foo:
+                    command: echo foo
+                    capture:
+                        var: FOO
+                        from: regex
+         "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(err[0].contains("Error loading profile"));
+    assert!(err[3].contains("Error building runtime"));
+    assert_eq!(err[4], "    1: Invalid capture on process \"foo\"");
+    assert_eq!(
+        err[5],
+        "    2: Expected regex to be provided when capturing from regex"
+    );
+    assert_eq!(0, out.len());
+});
@@ -1,6 +1,7 @@
 mod common;
 
 use common::TC;
+use std::process::Command;
 
 test!(single_job_with_single_task, |t: TC| {
     let (out, err) = t
@@ -321,6 +322,133 @@ test!(job_with_multiple_log_monitors, |t: TC| {
     assert_eq!(0, err.len());
 });
 
+test!(log_monitor_with_stream, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1; @m1 @m2
+
+            processes:
+                p1:
+                    command: |
+                        echo foo
+                        sleep 0.1
+                        echo foo >&2
+                        sleep 0.1
+                p2:
+                    command: echo bar
+                p3:
+                    command: echo baz
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    stream: stdout
+                    test: '[ "$ARPX_BUFFER" = foo ]'
+                    ontrigger: p2
+                m2:
+                    buffer_size: 1
+                    test: '[ "$ARPX_BUFFER" = foo ] && [ "$ARPX_STREAM" = stderr ]'
+                    ontrigger: p3
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[m1] bar", out[3]);
+    assert_eq!("[m2] baz", out[7]);
+    assert_eq!(10, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(output_with_carriage_returns_and_ansi_sequences, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1; @m1 @m2
+
+            processes:
+                p1:
+                    command: |
+                        printf 'progress 1\rprogress 2\rdone\r\n'
+                        sleep 0.1
+                        printf '\033[31mred\033[0m\n'
+                        sleep 0.1
+                p2:
+                    command: echo foo
+                p3:
+                    command: echo bar
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    test: '[ "$ARPX_BUFFER" = red ]'
+                    ontrigger: p2
+                m2:
+                    buffer_size: 1
+                    strip_ansi: false
+                    test: 'echo "$ARPX_BUFFER" | grep -q "\[31m"'
+                    ontrigger: p3
+        "#,
+        )
+        .opts("-j test --strip-ansi")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] done", out[1]);
+    assert_eq!("[p1] red", out[2]);
+    assert_btw!("[m1] foo", out, 3, 9);
+    assert_btw!("[m2] bar", out, 3, 9);
+    assert_eq!(10, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_and_log_monitor_with_multiple_actions, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1; @m1
+
+            processes:
+                p1:
+                    command: |
+                        echo foo
+                        sleep 0.1
+                    onsucceed: [p2, p3]
+                p2:
+                    command: |
+                        sleep 0.2
+                        echo bar
+                p3:
+                    command: echo baz
+                p4:
+                    command: |
+                        sleep 0.2
+                        echo qux
+                p5:
+                    command: echo quux
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    test: '[ "$ARPX_BUFFER" = foo ]'
+                    ontrigger: [p4, p5]
+                    concurrent_actions: true
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    let position = |line: &str| out.iter().position(|l| l == line).unwrap();
+
+    assert!(position("[p1] bar") < position("[p1] baz"));
+    assert!(position("[m1] quux") < position("[m1] qux"));
+    assert_eq!(15, out.len());
+    assert_eq!(0, err.len());
+});
+
 test!(job_overrides_process_contingency, |t: TC| {
     let (out, err) = t
         .profile(
@@ -335,8 +463,8 @@ test!(job_overrides_process_contingency, |t: TC| {
                     command: |
                         echo foo
                         sleep 0.1
-                    onsucceed: p3;
-                    onfail: p2;
+                    onsucceed: p3
+                    onfail: p2
                 p2:
                     command: |
                         echo bar
@@ -356,6 +484,1243 @@ test!(job_overrides_process_contingency, |t: TC| {
     assert_eq!("[p1] bar", out[4]);
     assert_eq!("[p3] baz", out[7]);
     assert_eq!("[p3] foo", out[10]);
-    assert_eq!(12, out.len());
+    assert_eq!("[p3] baz", out[13]);
+    assert_eq!(15, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_env, |t: TC| {
+    let env_file = t.file(
+        "test.env",
+        "# comment\nexport FOO=foo\nBAR='bar'\nBAZ=\"from file\"\n",
+    );
+    let (out, err) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: p1; @m1
+
+            processes:
+                p1:
+                    command: echo "$FOO $BAR $BAZ $QUX"
+                    env_file: {}
+                    env:
+                        BAZ: baz
+                        QUX: 1
+                p2:
+                    command: echo triggered
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    test: 'echo "$ARPX_BUFFER" | grep -q "$MATCH"'
+                    ontrigger: p2
+                    env:
+                        MATCH: foo bar baz 1
+        "#,
+            env_file
+        ))
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] foo bar baz 1", out[1]);
+    assert_btw!("[m1] triggered", out, 2, 6);
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_context_env, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2; @m1
+
+            processes:
+                p1:
+                    command: |
+                        echo "$ARPX_JOB $ARPX_TASK_INDEX $ARPX_PROCESS"
+                        [ -n "$ARPX_RUN_ID" ] && [ -d "$ARPX_PROFILE_DIR" ] && exit 3
+                    onfail: p3
+                p2:
+                    command: |
+                        echo foo
+                        sleep 0.1
+                p3:
+                    command: echo "$ARPX_PARENT_PROCESS $ARPX_PARENT_EXIT_CODE"
+                p4:
+                    command: echo "$ARPX_PARENT_PROCESS $ARPX_TRIGGER_BUFFER"
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    test: '[ "$ARPX_PROCESS" = p2 ] && [ "$ARPX_TASK_INDEX" = 2 ]'
+                    ontrigger: p4
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] test 1 p1", out[1]);
+    assert_eq!("[p1] p1 3", out[4]);
+    assert_btw!("[m1] p2 foo", out, 7, 11);
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_timeout, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: |
+                        echo foo
+                        sleep 5
+                        echo bar
+                    timeout: 0.2
+                    ontimeout: p3
+                    onfail: p4
+                p2:
+                    command: |
+                        sleep 5
+                    timeout: 0.2
+                    onfail: p4
+                p3:
+                    command: echo baz
+                p4:
+                    command: echo qux
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] foo", out[1]);
+    assert!(out[2].contains("\"p1\"") && out[2].ends_with("timed out"));
+    assert_eq!("[p1] baz", out[4]);
+    assert!(out[7].contains("\"p2\"") && out[7].ends_with("timed out"));
+    assert_eq!("[p2] qux", out[9]);
+    assert_eq!(11, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_timeout_after_output_closes, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: |
+                        exec >/dev/null 2>&1
+                        sleep 5
+                    timeout: 0.2
+                    ontimeout: p2
+                p2:
+                    command: echo foo
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(out[1].contains("\"p1\"") && out[1].ends_with("timed out"));
+    assert_eq!("[p1] foo", out[3]);
+    assert_eq!(5, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_timeout_kills_process_group, |t: TC| {
+    let pid_file = t.file("sleep.pid", "");
+    let (out, err) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: |
+                        (trap '' TERM; exec sleep 5 >/dev/null 2>&1) &
+                        echo $! > {}
+                        wait
+                    timeout: 0.2
+        "#,
+            pid_file
+        ))
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    let pid = std::fs::read_to_string(pid_file).unwrap();
+    let sleep_alive = Command::new("ps")
+        .args(["-o", "stat=", "-p", pid.trim()])
+        .output()
+        .map(|output| {
+            let stat = String::from_utf8_lossy(&output.stdout);
+
+            !stat.trim().is_empty() && !stat.starts_with('Z')
+        })
+        .unwrap();
+
+    assert!(out[1].contains("\"p1\"") && out[1].ends_with("timed out"));
+    assert!(!sleep_alive);
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_stop_signal, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: |
+                        trap 'echo cleaning up; exit 1' INT
+                        echo foo
+                        sleep 5 &
+                        wait
+                    timeout: 0.5
+                    stop_signal: SIGINT
+                    stop_timeout: 0.5
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] foo", out[1]);
+    assert_eq!("[p1] cleaning up", out[2]);
+    assert!(out[3].contains("\"p1\"") && out[3].ends_with("timed out"));
+    assert_eq!(4, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_tty, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: |
+                        test -t 1 && echo tty || echo pipe
+                        stty size
+                        echo foo >&2
+                    tty: true
+                p2:
+                    command: test -t 1 && echo tty || echo pipe
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!(vec!["[p1] tty", "[p1] 24 80", "[p1] foo"], out[1..4]);
+    assert!(out[4].contains("\"p1\"") && out[4].ends_with("succeeded"));
+    assert_eq!("[p2] pipe", out[6]);
+    assert_eq!(8, out.len());
     assert_eq!(0, err.len());
 });
+
+test!(process_with_stdin, |t: TC| {
+    let input_file = t.file("input.txt", "baz\n");
+    let (out, err) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+                    p3;
+
+            processes:
+                p1:
+                    command: cat
+                    stdin:
+                        text: |
+                            foo
+                            bar
+                p2:
+                    command: cat
+                    stdin:
+                        file: {}
+                p3:
+                    command: cat
+        "#,
+            input_file
+        ))
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!(vec!["[p1] foo", "[p1] bar"], out[1..3]);
+    assert_eq!("[p2] baz", out[5]);
+    assert!(out[8].contains("\"p3\"") && out[8].ends_with("succeeded"));
+    assert_eq!(9, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_readiness_probe, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: |
+                        echo starting
+                        sleep 0.2
+                        echo listening
+                        sleep 0.5
+                        echo done
+                    ready_when:
+                        log: ^listen
+                        interval: 0.05
+                p2:
+                    command: echo after
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!(vec!["[p1] starting", "[p1] listening"], out[1..3]);
+    assert!(out[3].contains("\"p1\"") && out[3].ends_with("ready"));
+    assert_eq!("[p2] after", out[5]);
+    assert_eq!("[p1] done", out[7]);
+    assert!(out[8].contains("\"p1\"") && out[8].ends_with("succeeded"));
+    assert_eq!(9, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_not_ready_in_time, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: sleep 5
+                    ready_when:
+                        file: does_not_exist
+                        interval: 0.05
+                        timeout: 0.2
+                    ontimeout: p3
+                p2:
+                    command: echo after
+                p3:
+                    command: echo not ready
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(out[1].contains("\"p1\"") && out[1].ends_with("not ready in time"));
+    assert!(out[2].contains("\"p1\"") && out[2].ends_with("timed out"));
+    assert_eq!("[p1] not ready", out[4]);
+    assert_eq!("[p2] after", out[7]);
+    assert_eq!(9, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_dependencies, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    [
+                        p1;
+                        p2;
+                        p3;
+                        p4;
+                    ]
+
+            processes:
+                p1:
+                    command: |
+                        sleep 0.2
+                        echo foo
+                p2:
+                    command: echo bar
+                    depends_on: [p1]
+                p3:
+                    command: exit 1
+                p4:
+                    command: echo baz
+                    depends_on: [p3]
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_btw!(
+        "\"p4\" skipped, dependency \"p3\" did not succeed",
+        out,
+        0,
+        4
+    );
+    assert_eq!("[p1] foo", out[4]);
+    assert!(out[6].contains("\"p2\"") && out[6].ends_with("spawned"));
+    assert_eq!("[p2] bar", out[7]);
+    assert_eq!(9, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_limits, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: ulimit -n
+                    limits:
+                        open_files: 64
+                p2:
+                    command: while :; do :; done
+                    limits:
+                        cpu: 1
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] 64", out[1]);
+    assert!(
+        out[4].contains("\"p2\"")
+            && out[4].ends_with("failed, killed by SIGXCPU (cpu limit exceeded)")
+    );
+    assert_eq!(5, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_user_group_and_umask, |t: TC| {
+    let id = |flag: &str| {
+        let output = Command::new("id").arg(flag).output().unwrap();
+
+        String::from_utf8(output.stdout).unwrap().trim().to_owned()
+    };
+    let (uid, gid) = (id("-u"), id("-g"));
+    let (out, err) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: |
+                        umask
+                        id -u
+                        id -g
+                    user: {}
+                    group: {}
+                    umask: "027"
+        "#,
+            uid, gid
+        ))
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] 0027", out[1]);
+    assert_eq!(format!("[p1] {}", uid), out[2]);
+    assert_eq!(format!("[p1] {}", gid), out[3]);
+    assert_eq!(5, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_and_log_monitor_with_bin, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1; @m1
+
+            processes:
+                p1:
+                    bin: echo
+                    args: ["running:"]
+                    command: foo bar
+                p2:
+                    command: echo baz
+            log_monitors:
+                m1:
+                    bin: bash
+                    args: ["-c"]
+                    buffer_size: 1
+                    test: '[[ "$ARPX_BUFFER" == *bar ]]'
+                    ontrigger: p2
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] running: foo bar", out[1]);
+    assert_btw!("baz", out, 2, 6);
+    assert_eq!(6, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_argv_command, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: [printf, '%s|%s\n', $FOO, "it's"]
+                    env:
+                        FOO: foo
+                p2:
+                    command: [printf, '%s|%s|%s\n', $FOO, "${FOO}bar", $$FOO]
+                    env:
+                        FOO: foo
+                    expand_env: true
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] $FOO|it's", out[1]);
+    assert_eq!("[p2] foo|foobar|$FOO", out[4]);
+    assert_eq!(6, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_fails_to_spawn, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: [arpx-does-not-exist, foo]
+                    retries: 1
+                    retry_delay: 0
+                    onfail: p3
+                p2:
+                    command: echo foo
+                    bin: arpx-does-not-exist
+                    onexit:
+                        127: p3
+                p3:
+                    command: echo bar
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(out[0].ends_with(
+        "\"p1\" failed, error spawning command: No such file or directory (os error 2)"
+    ));
+    assert!(out[1].ends_with("retrying in 0ns (attempt 2 of 2)"));
+    assert!(out[2].ends_with(
+        "\"p1\" failed, error spawning command: No such file or directory (os error 2)"
+    ));
+    assert_eq!("[p1] bar", out[4]);
+    assert!(out[6].ends_with(
+        "\"p2\" failed, error spawning command: No such file or directory (os error 2)"
+    ));
+    assert_eq!("[p2] bar", out[8]);
+    assert_eq!(10, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_capture, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+                    p3; @m1
+
+            processes:
+                p1:
+                    command: |
+                        echo building
+                        echo "tag: v1.2.3"
+                    capture:
+                        var: TAG
+                        from: regex
+                        regex: 'tag: (\S+)'
+                p2:
+                    command: |
+                        echo listening
+                        echo 8080
+                        echo
+                    capture:
+                        var: PORT
+                        from: last_line
+                p3:
+                    command: echo "$ARPX_VAR_TAG $ARPX_VAR_PORT"
+                p4:
+                    command: echo matched
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    test: '[ "$ARPX_BUFFER" = "$ARPX_VAR_TAG $ARPX_VAR_PORT" ]'
+                    ontrigger: p4
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p3] v1.2.3 8080", out[10]);
+    assert_btw!("matched", out, 11, 15);
+    assert_eq!(15, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_output_decoding, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+                    p3;
+
+            processes:
+                p1:
+                    command: printf 'caf\351\n'
+                p2:
+                    command: printf 'caf\351\n'
+                    encoding: latin-1
+                p3:
+                    command: printf 'abcdefgh\nxy'
+                    max_line_length: 4
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] caf\u{FFFD}", out[1]);
+    assert_eq!("[p2] café", out[4]);
+    assert_eq!("[p3] abcd", out[7]);
+    assert_eq!("[p3] xy", out[8]);
+    assert_eq!(10, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_retries, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: |
+                        echo "attempt $ARPX_ATTEMPT"
+                        [ "$ARPX_ATTEMPT" -eq 3 ]
+                    retries: 3
+                    retry_delay: 0.05
+                    backoff: exponential
+                    backoff_cap: 0.1
+                    onsucceed: p3
+                p2:
+                    command: |
+                        echo "attempt $ARPX_ATTEMPT"
+                        exit 1
+                    retries: 1
+                    onfail: p3
+                p3:
+                    command: echo foo
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] attempt 1", out[1]);
+    assert!(out[3].contains("\"p1\" retrying in 50ms (attempt 2 of 4)"));
+    assert_eq!("[p1] attempt 2", out[5]);
+    assert!(out[7].contains("\"p1\" retrying in 100ms (attempt 3 of 4)"));
+    assert_eq!("[p1] attempt 3", out[9]);
+    assert_eq!("[p1] foo", out[12]);
+    assert_eq!("[p2] attempt 1", out[15]);
+    assert_eq!("[p2] attempt 2", out[19]);
+    assert_eq!("[p2] foo", out[22]);
+    assert_eq!(24, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_restart, |t: TC| {
+    let count_file = t.file("count", "");
+    let (out, err) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: |
+                        echo run >> {}
+                        [ "$(wc -l < {})" -ge 3 ] && echo up
+                    restart: on-failure
+                    restart_delay: 0
+                    onsucceed: p2
+                p2:
+                    command: echo bar
+        "#,
+            count_file, count_file
+        ))
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(out[1].ends_with("failed"));
+    assert!(out[2].ends_with("restarting in 0ns (restart 1)"));
+    assert!(out[4].ends_with("failed"));
+    assert!(out[5].ends_with("restarting in 0ns (restart 2)"));
+    assert_eq!("[p1] up", out[7]);
+    assert!(out[8].ends_with("succeeded"));
+    assert_eq!("[p1] bar", out[10]);
+    assert_eq!(12, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_crash_loop, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: exit 1
+                    restart: always
+                    restart_delay: 0
+                    max_restarts: 2
+                    restart_window: 60
+                    onfail: p2
+                    oncrashloop: p3
+                p2:
+                    command: echo bar
+                p3:
+                    command: echo baz
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert!(out[8].ends_with("\"p1\" crash looping, restarted 2 times within 60s"));
+    assert_eq!("[p1] baz", out[10]);
+    assert_eq!(12, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(process_with_onexit, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+                    p3;
+                    p4;
+
+            processes:
+                p1:
+                    command: exit 2
+                    onexit:
+                        2: lint
+                        1-10: temp
+                    onfail: failed
+                p2:
+                    command: exit 75
+                    onexit:
+                        "64-78": temp
+                    onfail: failed
+                p3:
+                    command: kill -TERM $$
+                    onexit:
+                        SIGTERM: terminated
+                    onfail: failed
+                p4:
+                    command: exit 3
+                    onexit:
+                        2: lint
+                    onfail: failed
+                lint:
+                    command: echo lint
+                temp:
+                    command: echo temp
+                terminated:
+                    command: echo terminated
+                failed:
+                    command: echo failed
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    assert_eq!("[p1] lint", out[3]);
+    assert_eq!("[p2] temp", out[8]);
+    assert_eq!("[p3] terminated", out[13]);
+    assert_eq!("[p4] failed", out[18]);
+    assert_eq!(20, out.len());
+    assert_eq!(0, err.len());
+});
+
+test!(interrupt_stops_processes, |t: TC| {
+    let pid_file = t.file("sleep.pid", "");
+    let (out, err, status) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: |
+                    [
+                        p1;
+                        p2;
+                    ]
+
+            processes:
+                p1:
+                    command: |
+                        trap 'echo stopping; exit 0' INT
+                        echo foo
+                        sleep 5 &
+                        wait
+                p2:
+                    command: |
+                        sleep 5 &
+                        echo $! > {}
+                        wait
+        "#,
+            pid_file
+        ))
+        .opts("-j test --grace-period 0.3")
+        .signal("INT", 0.5)
+        .run_with_status()
+        .unwrap();
+
+    let pid = std::fs::read_to_string(pid_file).unwrap();
+    let sleep_alive = (0..20).all(|_| {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+
+        std::process::Command::new("ps")
+            .args(["-o", "stat=", "-p", pid.trim()])
+            .output()
+            .map(|output| {
+                let stat = String::from_utf8_lossy(&output.stdout);
+
+                !stat.trim().is_empty() && !stat.starts_with('Z')
+            })
+            .unwrap()
+    });
+
+    assert_eq!(Some(130), status.code());
+    assert!(out
+        .iter()
+        .any(|line| line.ends_with("Received SIGINT, stopping processes")));
+    assert!(out.contains(&"[p1] stopping".to_string()));
+    assert!(!sleep_alive);
+    assert_eq!(0, err.len());
+});
+
+test!(interrupt_stops_process_after_output_closes, |t: TC| {
+    let start = std::time::Instant::now();
+    let (out, err, status) = t
+        .profile(
+            r#"
+            jobs:
+                test: p1;
+
+            processes:
+                p1:
+                    command: |
+                        trap '' TERM
+                        exec >/dev/null 2>&1
+                        sleep 5
+        "#,
+        )
+        .opts("-j test --grace-period 0.3")
+        .signal("TERM", 0.5)
+        .run_with_status()
+        .unwrap();
+
+    assert_eq!(Some(143), status.code());
+    assert!(out
+        .iter()
+        .any(|line| line.ends_with("Received SIGTERM, stopping processes")));
+    assert!(out[2].contains("\"p1\"") && out[2].ends_with("stopped"));
+    assert!(start.elapsed() < std::time::Duration::from_secs(3));
+    assert_eq!(0, err.len());
+});
+
+test!(exit_action_stops_runtime, |t: TC| {
+    let (out, err, status) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    [
+                        p1 : arpx_exit_error;
+                        p2;
+                    ]
+                    p3;
+
+            processes:
+                p1:
+                    command: |
+                        sleep 0.2
+                        exit 1
+                p2:
+                    command: |
+                        trap 'echo stopping; exit 0' TERM
+                        sleep 5 &
+                        wait
+                p3:
+                    command: echo foo
+        "#,
+        )
+        .opts("-j test")
+        .run_with_status()
+        .unwrap();
+
+    assert_eq!(Some(1), status.code());
+    assert!(out.contains(&"[p2] stopping".to_string()));
+    assert!(out.iter().any(|line| line.ends_with("stopped")));
+    assert!(!out.iter().any(|line| line.contains("p3")));
+    assert_eq!(0, err.len());
+});
+
+test!(action_chain_exceeds_max_action_depth, |t: TC| {
+    let (out, err, status) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p3;
+
+            processes:
+                p1:
+                    command: exit 1
+                    onfail: p2
+                p2:
+                    command: echo foo
+                    onsucceed: p1
+                p3:
+                    command: echo bar
+        "#,
+        )
+        .opts("-j test --max-action-depth 3")
+        .run_with_status()
+        .unwrap();
+
+    assert_eq!(Some(1), status.code());
+    assert_eq!(2, out.iter().filter(|line| *line == "[p1] foo").count());
+    assert!(out.contains(
+        &"[p1] Action \"p1\" on process \"p2\" exceeded the maximum action depth of 3".to_string()
+    ));
+    assert!(!out.iter().any(|line| line.contains("bar")));
+    assert_eq!(0, err.len());
+});
+
+test!(builtin_exit_with_status, |t: TC| {
+    let (out, err, status) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    p1;
+                    p2;
+
+            processes:
+                p1:
+                    command: exit 1
+                    onfail: arpx_exit(3)
+                p2:
+                    command: echo foo
+        "#,
+        )
+        .opts("-j test")
+        .run_with_status()
+        .unwrap();
+
+    assert_eq!(Some(3), status.code());
+    assert!(out[1].ends_with("failed"));
+    assert!(!out.iter().any(|line| line.contains("foo")));
+    assert_eq!(0, err.len());
+});
+
+test!(builtin_kill_and_restart_task, |t: TC| {
+    let count_file = t.file("count", "");
+    let (out, err, status) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: |
+                    [
+                        p1;
+                        p2;
+                    ]
+                    p3;
+                    p4;
+
+            processes:
+                p1:
+                    command: sleep 0.2
+                    onsucceed: arpx_kill_task
+                p2:
+                    command: sleep 5
+                p3:
+                    command: |
+                        echo run >> {}
+                        [ "$(wc -l < {})" -ge 2 ]
+                    onfail: arpx_restart_task
+                    onsucceed: arpx_noop
+                p4:
+                    command: echo foo
+        "#,
+            count_file, count_file
+        ))
+        .opts("-j test")
+        .run_with_status()
+        .unwrap();
+
+    assert_eq!(Some(0), status.code());
+    assert!(out
+        .iter()
+        .any(|line| line.starts_with("[p2]") && line.ends_with("stopped")));
+    assert_eq!(
+        2,
+        out.iter()
+            .filter(|line| line.starts_with("[p3]") && line.ends_with("spawned"))
+            .count()
+    );
+    assert_eq!("[p4] foo", out[out.len() - 2]);
+    assert_eq!(0, err.len());
+});
+
+test!(log_monitor_signals_sibling_process, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    [
+                        p1;
+                        p2; @m1
+                    ]
+
+            processes:
+                p1:
+                    command: |
+                        trap 'echo reloading; exit 0' HUP
+                        sleep 5 &
+                        wait
+                p2:
+                    command: |
+                        sleep 0.2
+                        echo config changed
+            log_monitors:
+                m1:
+                    buffer_size: 1
+                    test: '[ "$ARPX_BUFFER" = "config changed" ]'
+                    ontrigger: arpx_signal:p1:HUP
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    let position = |line: &str| out.iter().position(|l| l == line).unwrap();
+
+    assert!(out
+        .iter()
+        .any(|line| line.starts_with("[m1] Sending SIGHUP to \"p1\"")));
+    assert!(position("[p2] config changed") < position("[p1] reloading"));
+    assert!(out
+        .iter()
+        .any(|line| line.starts_with("[p1] \"p1\"") && line.ends_with("succeeded")));
+    assert_eq!(0, err.len());
+});
+
+test!(builtin_restart_job_stops_running_processes, |t: TC| {
+    let count_file = t.file("count", "");
+    let (out, err, status) = t
+        .profile(&format!(
+            r#"
+            jobs:
+                test: |
+                    svc;
+                    p2;
+                    p3;
+
+            processes:
+                svc:
+                    command: |
+                        trap 'echo stopping; exit 0' TERM
+                        echo listening
+                        sleep 5 &
+                        wait
+                    ready_when:
+                        log: ^listen
+                        interval: 0.05
+                p2:
+                    command: |
+                        echo run >> {}
+                        [ "$(wc -l < {})" -ge 2 ]
+                    onfail: arpx_restart_job
+                p3:
+                    command: echo foo
+                    onsucceed: arpx_exit
+        "#,
+            count_file, count_file
+        ))
+        .opts("-j test")
+        .run_with_status()
+        .unwrap();
+
+    let positions = |line: &str| {
+        out.iter()
+            .enumerate()
+            .filter(|(_, l)| *l == line)
+            .map(|(i, _)| i)
+            .collect::<Vec<usize>>()
+    };
+    let listening = positions("[svc] listening");
+    let stopping = positions("[svc] stopping");
+
+    assert_eq!(Some(0), status.code());
+    assert_eq!(2, listening.len());
+    assert_eq!(2, stopping.len());
+    assert!(stopping[0] < listening[1]);
+    assert!(out.contains(&"[p3] foo".to_string()));
+    assert_eq!(0, err.len());
+});
+
+test!(builtin_noop_and_skip_task, |t: TC| {
+    let (out, err) = t
+        .profile(
+            r#"
+            jobs:
+                test: |
+                    [
+                        p1;
+                        p2;
+                    ]
+                    p3;
+
+            processes:
+                p1:
+                    command: sleep 0.1
+                    onsucceed: [arpx_noop, arpx_skip_task]
+                p2:
+                    command: |
+                        sleep 0.5
+                        echo bar
+                p3:
+                    command: echo foo
+        "#,
+        )
+        .opts("-j test")
+        .run()
+        .unwrap();
+
+    let position = |line: &str| out.iter().position(|l| l == line).unwrap();
+
+    assert!(position("[p3] foo") < position("[p2] bar"));
+    assert!(out
+        .iter()
+        .any(|line| line.starts_with("[p2] \"p2\"") && line.ends_with("succeeded")));
+    assert_eq!(0, err.len());
+});
+
+#[cfg(unix)]
+test!(embedded_runtime_restores_signal_dispositions, |t: TC| {
+    use std::os::unix::process::ExitStatusExt;
+
+    // The runtime is embedded in a child copy of this test, which should be terminated by the
+    // signal it raises once the runtime has run, as it was installed and closed twice.
+    if std::env::var("ARPX_TEST_EMBEDDED").is_ok() {
+        let profile = t.file(
+            "arpx.yaml",
+            "jobs:\n  test: p1;\nprocesses:\n  p1:\n    command: echo foo\n",
+        );
+
+        for _ in 0..2 {
+            arpx::Runtime::from_profile(&profile, &["test".to_string()])
+                .unwrap()
+                .run()
+                .unwrap();
+        }
+
+        unsafe { libc::raise(libc::SIGINT) };
+
+        return;
+    }
+
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "--exact",
+            "embedded_runtime_restores_signal_dispositions",
+            "--nocapture",
+        ])
+        .env("ARPX_TEST_EMBEDDED", "1")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .unwrap();
+
+    assert_eq!(Some(libc::SIGINT), status.signal());
+});